@@ -0,0 +1,7 @@
+#!/bin/sh
+# Builds the Python wheel without network access. Run `cargo fetch` once while online to fill the
+# cargo cache, and install maturin beforehand (`pip install maturin`).
+set -e
+
+cd "$(dirname "$0")"
+maturin build --release --offline --out dist "$@"
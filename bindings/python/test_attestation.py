@@ -0,0 +1,24 @@
+import json
+from pathlib import Path
+
+import attestation_rs
+
+EXAMPLE = Path(__file__).parents[2] / "attestation" / "example.json"
+
+
+def test_encode_decode_roundtrip():
+    attestation = json.loads(EXAMPLE.read_text())
+
+    decoded = attestation_rs.decode(attestation_rs.encode(attestation))
+
+    assert decoded["title"] == attestation["title"]
+    assert decoded["moduleAttributes"]["externalDependency"]["ercDeps"] == [20, 1155]
+
+
+def test_digest_accepts_json_and_dict():
+    text = EXAMPLE.read_text()
+
+    digest = attestation_rs.digest(text)
+
+    assert len(digest) == 32
+    assert digest == attestation_rs.digest(json.loads(text))
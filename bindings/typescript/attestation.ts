@@ -0,0 +1,345 @@
+// This file is generated by `cargo run -- --mode codegen --lang typescript`. Do not edit by hand.
+
+export type Address = `0x${string}`;
+export type Hex = `0x${string}`;
+
+export const ValidatorClassification = {
+  None: 0,
+  UnscopedValidator: 1,
+  ScopedValidator: 2,
+  Recovery: 3,
+  MultiPlexer: 4,
+} as const;
+
+export const ExecutorClassification = {
+  None: 0,
+  HandlesUserAssets: 1,
+  NoDelegateCall: 2,
+  TriggerByAccount: 3,
+  TriggerByRelayer: 4,
+  DeterministicExecution: 5,
+} as const;
+
+export const FallbackClassification = {
+  None: 0,
+  usesERC2771AccessControl: 1,
+  CalledWithStaticCall: 2,
+  CompatibiltyFallback: 3,
+  Callback: 4,
+} as const;
+
+export const HookClassification = {
+  None: 0,
+  AllowDefault: 1,
+  DenyDefault: 2,
+  AccessControl: 3,
+  ModuleControl: 4,
+  UserControl: 5,
+} as const;
+
+export const ExternalDependencyClassification = {
+  None: 0,
+  Oracles: 1,
+  Bridges: 2,
+  DEXs: 3,
+  Vaults: 4,
+  Registry: 5,
+  Lending: 6,
+  LiquidityProvision: 7,
+  Governance: 8,
+  Privacy: 9,
+  ZKProvers: 10,
+} as const;
+
+export const ERC7579ModuleType = {
+  None: 0,
+  Validator: 1,
+  Executor: 2,
+  Fallback: 3,
+  Hook: 4,
+} as const;
+
+export const SignatureType = {
+  None: 0,
+  SECP256K1: 1,
+  ERC1271: 2,
+  WEBAUTHN: 3,
+} as const;
+
+export interface Input {
+  title: string;
+  auditor: JsonAuditor;
+  reportUrl: string;
+  signer: Address;
+  moduleAttributes: JsonModuleAttributes;
+  signature?: JsonSignature | null;
+}
+
+export interface JsonAuditor {
+  name: string;
+  uri: string;
+  authors: string[];
+}
+
+export interface JsonModuleAttributes {
+  moduleAddress: Address;
+  globalAttributes: JsonGlobalAttributes;
+  validatorAttributes: JsonValidatorAttributes;
+  executorAttributes: JsonExecutorAttributes;
+  fallbackAttributes: JsonFallbackAttributes;
+  hookAttributes: JsonHookAttributes;
+  externalDependency: JsonExternalDependency;
+}
+
+export interface JsonSignature {
+  hash: Hex;
+  signature: Hex;
+  sigType?: "SECP256K1" | "ERC1271" | "WEBAUTHN";
+}
+
+export interface JsonGlobalAttributes {
+  reentrancyProtection: boolean;
+  importantDataValidation: boolean;
+  inputManipulationProtection: boolean;
+  emitsEvents: boolean;
+  moduleOwnerCantRug: boolean;
+  upgradeable: boolean;
+  pausable: boolean;
+  licensedModule: boolean;
+  erc7562StorageCompliant: boolean;
+  uninstallCleanUp: boolean;
+  multichainCompatible: boolean;
+}
+
+export const globalAttributesLayout = [
+  "reentrancyProtection",
+  "importantDataValidation",
+  "inputManipulationProtection",
+  "emitsEvents",
+  "moduleOwnerCantRug",
+  "upgradeable",
+  "pausable",
+  "licensedModule",
+  "erc7562StorageCompliant",
+  "uninstallCleanUp",
+  "multichainCompatible",
+] as const;
+
+export interface JsonValidatorAttributes {
+  unscopedValidator: boolean;
+  recoveryModule: boolean;
+  multiplexer: boolean;
+}
+
+export const validatorAttributesLayout = [
+  "unscopedValidator",
+  "recoveryModule",
+  "multiplexer",
+] as const;
+
+export interface JsonExecutorAttributes {
+  handlesUserAssets: boolean;
+  delegateCall: boolean;
+  triggeredByAccount: boolean;
+  triggeredByRelayer: boolean;
+  deterministicExecution: boolean;
+}
+
+export const executorAttributesLayout = [
+  "handlesUserAssets",
+  "delegateCall",
+  "triggeredByAccount",
+  "triggeredByRelayer",
+  "deterministicExecution",
+] as const;
+
+export interface JsonFallbackAttributes {
+  compatibilityFeature: boolean;
+  callbacks: boolean;
+}
+
+export const fallbackAttributesLayout = [
+  "compatibilityFeature",
+  "callbacks",
+] as const;
+
+export interface JsonHookAttributes {
+  defaultAllow: boolean;
+  defaultDeny: boolean;
+  accessControl: boolean;
+  moduleControl: boolean;
+  userControl: boolean;
+}
+
+export const hookAttributesLayout = [
+  "defaultAllow",
+  "defaultDeny",
+  "accessControl",
+  "moduleControl",
+  "userControl",
+] as const;
+
+export interface JsonExternalDependency {
+  oracle: boolean;
+  bridges: boolean;
+  dexs: boolean;
+  vaults: boolean;
+  registry: boolean;
+  lending: boolean;
+  liquidityProvision: boolean;
+  governance: boolean;
+  privacy: boolean;
+  zkProvers: boolean;
+  ercDeps: number[];
+}
+
+export const externalDependencyLayout = [
+  "oracle",
+  "bridges",
+  "dexs",
+  "vaults",
+  "registry",
+  "lending",
+  "liquidityProvision",
+  "governance",
+  "privacy",
+  "zkProvers",
+] as const;
+
+export const typeAttributesLayout = [
+  { moduleType: ERC7579ModuleType.Validator, key: "validatorAttributes", layout: validatorAttributesLayout },
+  { moduleType: ERC7579ModuleType.Executor, key: "executorAttributes", layout: executorAttributesLayout },
+  { moduleType: ERC7579ModuleType.Fallback, key: "fallbackAttributes", layout: fallbackAttributesLayout },
+  { moduleType: ERC7579ModuleType.Hook, key: "hookAttributes", layout: hookAttributesLayout },
+] as const;
+
+export const auditSummaryAbiParameters = [
+  {
+    name: "summary",
+    type: "tuple",
+    internalType: "struct AuditSummary",
+    components: [
+      { name: "title", type: "string" },
+      {
+        name: "auditor",
+        type: "tuple",
+        internalType: "struct Auditor",
+        components: [
+          { name: "name", type: "string" },
+          { name: "uri", type: "string" },
+          { name: "authors", type: "string[]" },
+        ],
+      },
+      {
+        name: "moduleAttributes",
+        type: "tuple",
+        internalType: "struct ModuleAttributes",
+        components: [
+          { name: "moduleAddress", type: "address" },
+          { name: "packedAttributes", type: "bytes" },
+          {
+            name: "typeAttributes",
+            type: "tuple[]",
+            internalType: "struct ModuleTypeAttributes[]",
+            components: [
+              { name: "moduleType", type: "uint8", internalType: "enum ERC7579ModuleType" },
+              { name: "encodedAttributes", type: "bytes" },
+            ],
+          },
+          { name: "packedExternalDependency", type: "bytes" },
+          { name: "ercDeps", type: "uint16[]" },
+        ],
+      },
+      {
+        name: "signature",
+        type: "tuple",
+        internalType: "struct Signature",
+        components: [
+          { name: "sigType", type: "uint8", internalType: "enum SignatureType" },
+          { name: "signer", type: "address" },
+          { name: "signatureData", type: "bytes" },
+          { name: "hash", type: "bytes32" },
+        ],
+      },
+    ],
+  },
+] as const;
+
+export const digestAbiParameters = [
+  {
+    name: "digest",
+    type: "tuple",
+    internalType: "struct Digest",
+    components: [
+      { name: "title", type: "string" },
+      {
+        name: "auditor",
+        type: "tuple",
+        internalType: "struct Auditor",
+        components: [
+          { name: "name", type: "string" },
+          { name: "uri", type: "string" },
+          { name: "authors", type: "string[]" },
+        ],
+      },
+      {
+        name: "moduleAttributes",
+        type: "tuple",
+        internalType: "struct ModuleAttributes",
+        components: [
+          { name: "moduleAddress", type: "address" },
+          { name: "packedAttributes", type: "bytes" },
+          {
+            name: "typeAttributes",
+            type: "tuple[]",
+            internalType: "struct ModuleTypeAttributes[]",
+            components: [
+              { name: "moduleType", type: "uint8", internalType: "enum ERC7579ModuleType" },
+              { name: "encodedAttributes", type: "bytes" },
+            ],
+          },
+          { name: "packedExternalDependency", type: "bytes" },
+          { name: "ercDeps", type: "uint16[]" },
+        ],
+      },
+    ],
+  },
+] as const;
+
+export const webAuthnSignatureAbiParameters = [
+  {
+    name: "signature",
+    type: "tuple",
+    internalType: "struct WebAuthnSignature",
+    components: [
+      { name: "authenticatorData", type: "bytes" },
+      { name: "clientDataJSON", type: "string" },
+      { name: "challengeIndex", type: "uint256" },
+      { name: "typeIndex", type: "uint256" },
+      { name: "r", type: "bytes32" },
+      { name: "s", type: "bytes32" },
+      { name: "x", type: "bytes32" },
+      { name: "y", type: "bytes32" },
+    ],
+  },
+] as const;
+
+export function packAttributes<K extends string>(
+  layout: readonly K[],
+  attributes: Record<K, boolean>,
+): Hex {
+  return `0x${layout.map((key) => (attributes[key] ? "01" : "00")).join("")}`;
+}
+
+export function unpackAttributes<K extends string>(
+  layout: readonly K[],
+  packed: Hex,
+): Record<K, boolean> {
+  const bytes = packed.slice(2);
+  if (bytes.length !== layout.length * 2) {
+    throw new Error(`Invalid byte length ${bytes.length / 2}, expected ${layout.length}`);
+  }
+  return Object.fromEntries(
+    layout.map((key, index) => [key, bytes.slice(index * 2, index * 2 + 2) !== "00"]),
+  ) as Record<K, boolean>;
+}
@@ -0,0 +1,32 @@
+//! Serves the mock signer used by the remote signer tests, for trying out `remote` signer
+//! settings without a signing service:
+//!
+//! ```sh
+//! cargo run --example mock_signer -- 127.0.0.1:8545 <private key> [token]
+//! ```
+//!
+//! The key defaults to the one `test/Schema.t.sol` signs with.
+
+#[path = "../src/mock_signer.rs"]
+mod mock_signer;
+
+use alloy_signer_local::PrivateKeySigner;
+use std::error::Error;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = std::env::args().skip(1);
+    let addr = args.next().unwrap_or_else(|| "127.0.0.1:8545".to_string());
+    let key: PrivateKeySigner = args
+        .next()
+        .as_deref()
+        .unwrap_or("0x5ed8e65ce779308a499dfcb4b2d37a267847e084d80d1b51b09f0bf97e5b8319")
+        .parse()?;
+    let token = args.next();
+
+    let address = key.address();
+    let server = mock_signer::MockSigner::bind(addr, key, token)?;
+    eprintln!("signing for {} at {}", address, server.url());
+    loop {
+        std::thread::park();
+    }
+}
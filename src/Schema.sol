@@ -1,10 +1,10 @@
 // SPDX-License-Identifier: UNLICENSED
+// This file is generated by `cargo run -- --mode codegen --lang solidity`. Do not edit by hand.
 pragma solidity ^0.8.13;
 
 import "solady/utils/SignatureCheckerLib.sol";
 import "solady/utils/ECDSA.sol";
-
-import "forge-std/console2.sol";
+import "solady/utils/Base64.sol";
 
 enum ValidatorClassification {
     None,
@@ -72,13 +72,14 @@ struct ModuleAttributes {
     bytes packedAttributes;
     ModuleTypeAttributes[] typeAttributes;
     bytes packedExternalDependency;
+    uint16[] ercDeps;
 }
-// uint16 ercDeps;
 
 enum SignatureType {
     None,
     SECP256K1,
-    ERC1271
+    ERC1271,
+    WEBAUTHN
 }
 
 struct Auditor {
@@ -94,6 +95,17 @@ struct Signature {
     bytes32 hash;
 }
 
+struct WebAuthnSignature {
+    bytes authenticatorData;
+    string clientDataJSON;
+    uint256 challengeIndex;
+    uint256 typeIndex;
+    bytes32 r;
+    bytes32 s;
+    bytes32 x;
+    bytes32 y;
+}
+
 struct AuditSummary {
     string title;
     Auditor auditor;
@@ -107,11 +119,12 @@ struct Digest {
     ModuleAttributes moduleAttributes;
 }
 
-//string foobar = "(enum ERC7579ModuleType (None,Validator,Executor,Fallback,Hook),struct ModuleTypeAttributes (ERC7579ModuleType moduleType,bytes encodedAttributes),struct ModuleAttributes (address moduleAddress,bytes packedAttributes,ModuleTypeAttributes[] typeAttributes,bytes packedExternalDependency),enum SignatureType (None,SECP256K1,ERC1271),struct Auditor (string name,string uri,string[] authors),struct Signature (SignatureType sigType,address signer,bytes signatureData,bytes32 hash),struct AuditSummary (string title,Auditor auditor,ModuleAttributes moduleAttributes,Signature signature))");
-
 contract Schema {
     using SignatureCheckerLib for address;
 
+    /// RIP-7212 precompile verifying P-256 signatures
+    address public constant P256_VERIFIER = address(0x100);
+
     function decode(bytes memory data) public pure returns (AuditSummary memory summary) {
         summary = abi.decode(data, (AuditSummary));
     }
@@ -129,5 +142,59 @@ contract Schema {
                 ECDSA.recover(ECDSA.toEthSignedMessageHash(summary.signature.hash), summary.signature.signatureData);
             return recover == summary.signature.signer;
         }
+        if (summary.signature.sigType == SignatureType.ERC1271) {
+            return summary.signature.signer.isValidERC1271SignatureNow(
+                summary.signature.hash, summary.signature.signatureData
+            );
+        }
+        if (summary.signature.sigType == SignatureType.WEBAUTHN) {
+            return validateWebAuthn(
+                summary.signature.hash,
+                summary.signature.signer,
+                abi.decode(summary.signature.signatureData, (WebAuthnSignature))
+            );
+        }
+    }
+
+    /// Checks a passkey assertion over `hash`. `signer` must be the address of the P-256 key,
+    /// the last 20 bytes of `keccak256(abi.encode(x, y))`.
+    function validateWebAuthn(bytes32 hash, address signer, WebAuthnSignature memory auth)
+        public
+        view
+        returns (bool)
+    {
+        if (address(uint160(uint256(keccak256(abi.encode(auth.x, auth.y))))) != signer) {
+            return false;
+        }
+        // User presence flag, after the 32-byte RP ID hash
+        if (auth.authenticatorData.length < 37 || auth.authenticatorData[32] & bytes1(0x01) == 0) {
+            return false;
+        }
+        bytes memory clientData = bytes(auth.clientDataJSON);
+        if (!contains(clientData, auth.typeIndex, '"type":"webauthn.get"')) {
+            return false;
+        }
+        bytes memory challenge =
+            abi.encodePacked('"challenge":"', Base64.encode(abi.encodePacked(hash), true, true), '"');
+        if (!contains(clientData, auth.challengeIndex, challenge)) {
+            return false;
+        }
+
+        bytes32 message = sha256(abi.encodePacked(auth.authenticatorData, sha256(clientData)));
+        (bool success, bytes memory result) =
+            P256_VERIFIER.staticcall(abi.encode(message, auth.r, auth.s, auth.x, auth.y));
+        return success && result.length == 32 && abi.decode(result, (uint256)) == 1;
+    }
+
+    function contains(bytes memory data, uint256 index, bytes memory expected) internal pure returns (bool) {
+        if (index > data.length || expected.length > data.length - index) {
+            return false;
+        }
+        for (uint256 i; i < expected.length; i++) {
+            if (data[index + i] != expected[i]) {
+                return false;
+            }
+        }
+        return true;
     }
 }
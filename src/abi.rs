@@ -1,11 +1,11 @@
 use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
-use alloy_sol_types::{abi, sol};
-use alloy_sol_types::{sol_data::*, SolValue};
-use hex;
+use alloy_sol_types::sol;
+use alloy_sol_types::SolValue;
 
 use crate::types::{
-    Input, JsonExecutorAttributes, JsonExternalDependency, JsonFallbackAttributes,
-    JsonGlobalAttributes, JsonHookAttributes, JsonModuleAttributes, JsonValidatorAttributes,
+    Input, JsonAuditor, JsonExecutorAttributes, JsonExternalDependency, JsonFallbackAttributes,
+    JsonGlobalAttributes, JsonHookAttributes, JsonModuleAttributes, JsonSignature,
+    JsonSignatureType, JsonValidatorAttributes,
 };
 use std::error::Error;
 
@@ -19,8 +19,18 @@ uint8 v;
 }
 }
 
-sol! {
+/// Declares the schema types once: the tokens are expanded by `sol!` into the Rust bindings and
+/// kept verbatim in [`SCHEMA_SOURCE`] so that `codegen` can emit the matching Solidity.
+macro_rules! sol_schema {
+    ($($item:tt)*) => {
+        sol! { $($item)* }
+
+        /// Token stream of the schema `sol!` block.
+        pub const SCHEMA_SOURCE: &str = stringify!($($item)*);
+    };
+}
 
+sol_schema! {
 
 #[derive(Debug)]
 enum ValidatorClassification {
@@ -99,11 +109,12 @@ struct ModuleAttributes {
     uint16[] ercDeps;
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 enum SignatureType {
     None,
     SECP256K1,
-    ERC1271
+    ERC1271,
+    WEBAUTHN
 }
 
 #[derive(Debug)]
@@ -121,6 +132,18 @@ struct Signature {
     bytes32 hash;
 }
 
+#[derive(Debug, PartialEq)]
+struct WebAuthnSignature {
+    bytes authenticatorData;
+    string clientDataJSON;
+    uint256 challengeIndex;
+    uint256 typeIndex;
+    bytes32 r;
+    bytes32 s;
+    bytes32 x;
+    bytes32 y;
+}
+
 #[derive(Debug)]
 struct AuditSummary {
     string title;
@@ -129,14 +152,38 @@ struct AuditSummary {
     Signature signature;
 }
 
+#[derive(Debug)]
+struct Digest {
+    string title;
+    Auditor auditor;
+    ModuleAttributes moduleAttributes;
+}
+
 }
 
 pub trait PackableAttributes {
+    /// JSON keys of the packed flags, in byte order.
+    const LAYOUT: &'static [&'static str];
+
     fn pack(&self) -> Bytes;
     fn unpack(bytes: &Bytes) -> Result<Box<Self>, Box<dyn Error>>;
 }
 
 impl PackableAttributes for JsonGlobalAttributes {
+    const LAYOUT: &'static [&'static str] = &[
+        "reentrancyProtection",
+        "importantDataValidation",
+        "inputManipulationProtection",
+        "emitsEvents",
+        "moduleOwnerCantRug",
+        "upgradeable",
+        "pausable",
+        "licensedModule",
+        "erc7562StorageCompliant",
+        "uninstallCleanUp",
+        "multichainCompatible",
+    ];
+
     fn pack(&self) -> Bytes {
         let list = vec![
             self.reentrancy_protection as u8,
@@ -154,7 +201,7 @@ impl PackableAttributes for JsonGlobalAttributes {
         Bytes::from(list)
     }
     fn unpack(bytes: &Bytes) -> Result<Box<Self>, Box<dyn Error>> {
-        if bytes.len() != 10 {
+        if bytes.len() != Self::LAYOUT.len() {
             return Err("Invalid byte length for JsonGlobalAttributes".into());
         }
         Ok(Box::new(JsonGlobalAttributes {
@@ -174,6 +221,12 @@ impl PackableAttributes for JsonGlobalAttributes {
 }
 
 impl PackableAttributes for JsonValidatorAttributes {
+    const LAYOUT: &'static [&'static str] = &[
+        "unscopedValidator",
+        "recoveryModule",
+        "multiplexer",
+    ];
+
     fn pack(&self) -> Bytes {
         let list = vec![
             self.unscoped_validator as u8,
@@ -184,7 +237,7 @@ impl PackableAttributes for JsonValidatorAttributes {
         Bytes::from(list)
     }
     fn unpack(bytes: &Bytes) -> Result<Box<Self>, Box<dyn Error>> {
-        if bytes.len() != 3 {
+        if bytes.len() != Self::LAYOUT.len() {
             return Err("Invalid byte length for JsonValidatorAttributes".into());
         }
         Ok(Box::new(JsonValidatorAttributes {
@@ -196,6 +249,14 @@ impl PackableAttributes for JsonValidatorAttributes {
 }
 
 impl PackableAttributes for JsonExecutorAttributes {
+    const LAYOUT: &'static [&'static str] = &[
+        "handlesUserAssets",
+        "delegateCall",
+        "triggeredByAccount",
+        "triggeredByRelayer",
+        "deterministicExecution",
+    ];
+
     fn pack(&self) -> Bytes {
         let list = vec![
             self.handles_user_assets as u8,
@@ -208,8 +269,7 @@ impl PackableAttributes for JsonExecutorAttributes {
         Bytes::from(list)
     }
     fn unpack(bytes: &Bytes) -> Result<Box<Self>, Box<dyn Error>> {
-        if bytes.len() != 5 {
-            // Changed from 3 to 5
+        if bytes.len() != Self::LAYOUT.len() {
             return Err("Invalid byte length for JsonExecutorAttributes".into());
         }
         Ok(Box::new(JsonExecutorAttributes {
@@ -223,13 +283,18 @@ impl PackableAttributes for JsonExecutorAttributes {
 }
 
 impl PackableAttributes for JsonFallbackAttributes {
+    const LAYOUT: &'static [&'static str] = &[
+        "compatibilityFeature",
+        "callbacks",
+    ];
+
     fn pack(&self) -> Bytes {
         let list = vec![self.compatibility_feature as u8, self.callbacks as u8];
 
         Bytes::from(list)
     }
     fn unpack(bytes: &Bytes) -> Result<Box<Self>, Box<dyn Error>> {
-        if bytes.len() != 2 {
+        if bytes.len() != Self::LAYOUT.len() {
             return Err("Invalid byte length for JsonFallbackAttributes".into());
         }
         Ok(Box::new(JsonFallbackAttributes {
@@ -240,6 +305,14 @@ impl PackableAttributes for JsonFallbackAttributes {
 }
 
 impl PackableAttributes for JsonHookAttributes {
+    const LAYOUT: &'static [&'static str] = &[
+        "defaultAllow",
+        "defaultDeny",
+        "accessControl",
+        "moduleControl",
+        "userControl",
+    ];
+
     fn pack(&self) -> Bytes {
         let list = vec![
             self.default_allow as u8,
@@ -252,7 +325,7 @@ impl PackableAttributes for JsonHookAttributes {
         Bytes::from(list)
     }
     fn unpack(bytes: &Bytes) -> Result<Box<Self>, Box<dyn Error>> {
-        if bytes.len() != 5 {
+        if bytes.len() != Self::LAYOUT.len() {
             return Err("Invalid byte length for JsonHookAttributes".into());
         }
         Ok(Box::new(JsonHookAttributes {
@@ -266,6 +339,19 @@ impl PackableAttributes for JsonHookAttributes {
 }
 
 impl PackableAttributes for JsonExternalDependency {
+    const LAYOUT: &'static [&'static str] = &[
+        "oracle",
+        "bridges",
+        "dexs",
+        "vaults",
+        "registry",
+        "lending",
+        "liquidityProvision",
+        "governance",
+        "privacy",
+        "zkProvers",
+    ];
+
     fn pack(&self) -> Bytes {
         let list = vec![
             self.oracle as u8,
@@ -284,7 +370,7 @@ impl PackableAttributes for JsonExternalDependency {
     }
 
     fn unpack(bytes: &Bytes) -> Result<Box<Self>, Box<dyn Error>> {
-        if bytes.len() != 10 {
+        if bytes.len() != Self::LAYOUT.len() {
             return Err("Invalid byte length for JsonExternalDependency".into());
         }
         Ok(Box::new(JsonExternalDependency {
@@ -305,10 +391,24 @@ impl PackableAttributes for JsonExternalDependency {
 
 pub trait ParseAttributes {
     fn encode(&self) -> ModuleAttributes;
+    fn decode(attributes: &ModuleAttributes) -> Result<Box<Self>, Box<dyn Error>>;
 }
+
+fn type_attributes(
+    attributes: &ModuleAttributes,
+    module_type: ERC7579ModuleType,
+) -> Result<&Bytes, Box<dyn Error>> {
+    attributes
+        .typeAttributes
+        .iter()
+        .find(|type_attributes| type_attributes.moduleType == module_type)
+        .map(|type_attributes| &type_attributes.encodedAttributes)
+        .ok_or_else(|| format!("Missing {:?} type attributes", module_type).into())
+}
+
 impl ParseAttributes for JsonModuleAttributes {
     fn encode(&self) -> ModuleAttributes {
-        let module_attributes = ModuleAttributes {
+        ModuleAttributes {
             moduleAddress: self.module_address,
             packedAttributes: self.global_attributes.pack(),
             typeAttributes: vec![
@@ -331,33 +431,65 @@ impl ParseAttributes for JsonModuleAttributes {
             ],
             packedExternalDependency: self.external_dependency.pack(),
             ercDeps: self.external_dependency.erc_deps.clone(),
-        };
-
-        module_attributes
+        }
     }
-}
 
-fn hex_string_to_vec(hex_string: &str) -> Result<Vec<u8>, hex::FromHexError> {
-    // Remove "0x" prefix if present
-    let cleaned_hex = hex_string.strip_prefix("0x").unwrap_or(hex_string);
-
-    // Decode the hex string
-    hex::decode(cleaned_hex)
+    fn decode(attributes: &ModuleAttributes) -> Result<Box<Self>, Box<dyn Error>> {
+        let mut external_dependency =
+            JsonExternalDependency::unpack(&attributes.packedExternalDependency)?;
+        external_dependency.erc_deps = attributes.ercDeps.clone();
+
+        Ok(Box::new(JsonModuleAttributes {
+            module_address: attributes.moduleAddress,
+            global_attributes: *JsonGlobalAttributes::unpack(&attributes.packedAttributes)?,
+            validator_attributes: *JsonValidatorAttributes::unpack(type_attributes(
+                attributes,
+                ERC7579ModuleType::Validator,
+            )?)?,
+            executor_attributes: *JsonExecutorAttributes::unpack(type_attributes(
+                attributes,
+                ERC7579ModuleType::Executor,
+            )?)?,
+            fallback_attributes: *JsonFallbackAttributes::unpack(type_attributes(
+                attributes,
+                ERC7579ModuleType::Fallback,
+            )?)?,
+            hook_attributes: *JsonHookAttributes::unpack(type_attributes(
+                attributes,
+                ERC7579ModuleType::Hook,
+            )?)?,
+            external_dependency: *external_dependency,
+        }))
+    }
 }
 
 pub trait SignAttestation {
+    /// Encodes the attestation as an `AuditSummary`. `sig_type` applies to unsigned attestations,
+    /// signed ones keep the type of their signature.
     fn encode(&self, sig_type: SignatureType, signer: Address) -> AuditSummary;
+    /// Rebuilds the attestation from an `AuditSummary`. `reportUrl` is not part of the on-chain
+    /// encoding and is left empty.
+    fn decode(summary: &AuditSummary) -> Result<Box<Self>, Box<dyn Error>>;
+}
+
+/// Decodes ABI-encoded `AuditSummary` bytes, as printed by `bytes` mode.
+pub fn decode_summary(data: &[u8]) -> Result<AuditSummary, Box<dyn Error>> {
+    Ok(AuditSummary::abi_decode(data, true)?)
 }
 
 impl SignAttestation for Input {
-    fn encode(&self, sig_type: SignatureType, signer: Address) -> AuditSummary {
-        let mut hash: U256 = "42".parse().unwrap();
+    fn encode(&self, sig_type: SignatureType, _signer: Address) -> AuditSummary {
+        let hash: U256 = "42".parse().unwrap();
 
         let signature: Bytes = self
             .signature
             .as_ref()
             .map(|json_sig| json_sig.signature.clone())
-            .unwrap_or_else(Bytes::default);
+            .unwrap_or_default();
+        let sig_type = match &self.signature {
+            Some(json_sig) => json_sig.sig_type.into(),
+            None => sig_type,
+        };
 
         let mut summary = AuditSummary {
             title: self.title.clone(),
@@ -377,20 +509,49 @@ impl SignAttestation for Input {
 
         let actual_hash = summary.digest();
 
-        summary.signature.hash = actual_hash.into();
+        summary.signature.hash = actual_hash;
 
         summary
     }
-}
 
-sol! {
-    #[derive(Debug)]
-    struct Digest {
-        string title;
-        Auditor auditor;
-        ModuleAttributes moduleAttributes;
+    fn decode(summary: &AuditSummary) -> Result<Box<Self>, Box<dyn Error>> {
+        let signature = if summary.signature.signatureData.is_empty() {
+            None
+        } else {
+            Some(JsonSignature {
+                hash: summary.signature.hash.to_string(),
+                signature: summary.signature.signatureData.clone(),
+                sig_type: match summary.signature.sigType {
+                    SignatureType::ERC1271 => JsonSignatureType::Erc1271,
+                    SignatureType::WEBAUTHN => JsonSignatureType::WebAuthn,
+                    _ => JsonSignatureType::Secp256k1,
+                },
+            })
+        };
+
+        Ok(Box::new(Input {
+            title: summary.title.clone(),
+            auditor: JsonAuditor {
+                name: summary.auditor.name.clone(),
+                uri: summary.auditor.uri.clone(),
+                authors: summary.auditor.authors.clone(),
+            },
+            report_url: String::new(),
+            signer: summary.signature.signer,
+            module_attributes: *JsonModuleAttributes::decode(&summary.moduleAttributes)?,
+            signature,
+        }))
     }
+}
 
+impl From<JsonSignatureType> for SignatureType {
+    fn from(sig_type: JsonSignatureType) -> Self {
+        match sig_type {
+            JsonSignatureType::Secp256k1 => SignatureType::SECP256K1,
+            JsonSignatureType::Erc1271 => SignatureType::ERC1271,
+            JsonSignatureType::WebAuthn => SignatureType::WEBAUTHN,
+        }
+    }
 }
 
 pub trait HashAuditSummary {
@@ -422,6 +583,7 @@ impl HashAuditSummary for AuditSummary {
 mod tests {
     use super::*;
     use alloy_primitives::{Address, Bytes};
+    use crate::types::tests::example;
 
     #[test]
     fn test_global_attributes_pack_unpack() {
@@ -436,6 +598,7 @@ mod tests {
             licensed_module: true,
             erc7562_storage_compliant: false,
             uninstall_clean_up: true,
+            multichain_compatible: false,
         };
 
         let packed = global_attrs.pack();
@@ -462,7 +625,7 @@ mod tests {
     fn test_executor_attributes_pack_unpack() {
         let executor_attrs = JsonExecutorAttributes {
             handles_user_assets: true,
-            no_delegate_call: false,
+            delegate_call: false,
             triggered_by_account: true,
             triggered_by_relayer: false,
             deterministic_execution: true,
@@ -525,6 +688,32 @@ mod tests {
         assert_eq!(*unpacked, external_dependency);
     }
 
+    fn assert_layout_matches_pack<T>(base: serde_json::Value)
+    where
+        T: PackableAttributes + serde::de::DeserializeOwned,
+    {
+        for (index, key) in T::LAYOUT.iter().enumerate() {
+            let mut value = base.clone();
+            for other in T::LAYOUT {
+                value[*other] = (other == key).into();
+            }
+            let attrs: T = serde_json::from_value(value).unwrap();
+
+            let expected: Vec<u8> = (0..T::LAYOUT.len()).map(|i| (i == index) as u8).collect();
+            assert_eq!(attrs.pack().to_vec(), expected, "{} is not at byte {}", key, index);
+        }
+    }
+
+    #[test]
+    fn test_layouts_match_pack_order() {
+        assert_layout_matches_pack::<JsonGlobalAttributes>(serde_json::json!({}));
+        assert_layout_matches_pack::<JsonValidatorAttributes>(serde_json::json!({}));
+        assert_layout_matches_pack::<JsonExecutorAttributes>(serde_json::json!({}));
+        assert_layout_matches_pack::<JsonFallbackAttributes>(serde_json::json!({}));
+        assert_layout_matches_pack::<JsonHookAttributes>(serde_json::json!({}));
+        assert_layout_matches_pack::<JsonExternalDependency>(serde_json::json!({ "ercDeps": [] }));
+    }
+
     #[test]
     fn test_module_attributes_encode() {
         let module_attrs = JsonModuleAttributes {
@@ -540,6 +729,7 @@ mod tests {
                 licensed_module: true,
                 erc7562_storage_compliant: false,
                 uninstall_clean_up: true,
+                multichain_compatible: false,
             },
             validator_attributes: JsonValidatorAttributes {
                 unscoped_validator: true,
@@ -629,10 +819,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_input_encode_decode() {
+        let mut input = example();
+        input.report_url = String::new();
+
+        let summary = input.encode(SignatureType::SECP256K1, input.signer);
+        let data = summary.abi_encode();
+        let decoded = Input::decode(&decode_summary(&data).unwrap()).unwrap();
+
+        assert_eq!(*decoded, input);
+    }
+
     #[test]
     fn test_invalid_byte_length() {
-        // Invalid length for JsonGlobalAttributes (expects 10 bytes)
-        let invalid_global = Bytes::from(vec![0; 9]);
+        // Invalid length for JsonGlobalAttributes (expects 11 bytes)
+        let invalid_global = Bytes::from(vec![0; 10]);
         assert!(JsonGlobalAttributes::unpack(&invalid_global).is_err());
 
         // Invalid length for JsonValidatorAttributes (expects 3 bytes)
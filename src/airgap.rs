@@ -0,0 +1,321 @@
+//! Two-phase signing for keys on an offline machine. `prepare` writes a signing request holding
+//! what the offline side needs: the input being signed, its digest and the signer. `sign-request`
+//! recomputes the digest from that input, refuses requests whose digest does not match, and shows
+//! a summary rendered from that same input before signing it into a detached signature.
+//! `finalize` attaches that signature to the input on the online machine.
+//!
+//! The request also fits a QR code as a one-line payload. It carries only the digest, the signer
+//! and the fields to recognize the attestation by, so its size does not grow with the input:
+//!
+//! ```text
+//! attestation:sign?v=2&digest=0x…&signer=0x…&sigType=SECP256K1&title=…&auditor=…&module=0x…
+//! ```
+//!
+//! Those fields are not covered by the digest, so a request signed from its payload must have its
+//! digest compared with the one `prepare` printed.
+
+use alloy_primitives::{Address, Bytes, B256};
+use qrcode::render::unicode::Dense1x2;
+use qrcode::QrCode;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+use attestation_rs::abi::{SignAttestation, SignatureType};
+use attestation_rs::detached::DetachedSignature;
+use attestation_rs::render;
+use attestation_rs::types::{checksummed, Input};
+
+use crate::signer::from_hex_string;
+
+pub const REQUEST_SCHEMA_VERSION: u32 = 2;
+
+const PAYLOAD_PREFIX: &str = "attestation:sign?";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SigningRequest {
+    pub schema_version: u32,
+    pub digest: B256,
+    pub sig_type: String,
+    #[serde(serialize_with = "checksummed")]
+    pub signer: Address,
+    /// The input being signed, without any signature it already carries. Only request files
+    /// have it, scanned payloads do not.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<Input>,
+    /// What is being signed, for the offline side to review. Rendered from `input` when there is
+    /// one, from the payload fields otherwise.
+    #[serde(default)]
+    pub summary: String,
+    /// The request as one line of text, for QR codes
+    #[serde(default)]
+    pub payload: String,
+}
+
+impl SigningRequest {
+    pub fn new(input: &Input) -> Self {
+        let mut input = input.clone();
+        input.signature = None;
+        let digest = digest(&input);
+        let sig_type = format!("{:?}", SignatureType::SECP256K1);
+        let payload = format!(
+            "{}v={}&digest={}&signer={}&sigType={}&title={}&auditor={}&module={}",
+            PAYLOAD_PREFIX,
+            REQUEST_SCHEMA_VERSION,
+            digest,
+            input.signer.to_checksum(None),
+            sig_type,
+            escape(&input.title),
+            escape(&input.auditor.name),
+            input.module_attributes.module_address.to_checksum(None)
+        );
+        SigningRequest {
+            schema_version: REQUEST_SCHEMA_VERSION,
+            digest,
+            sig_type,
+            signer: input.signer,
+            summary: render::text(&input),
+            input: Some(input),
+            payload,
+        }
+    }
+
+    /// Recomputes the digest from the request's input, failing if it is not the digest or signer
+    /// the request claims, so that what is reviewed is what gets signed. Requests without an
+    /// input, scanned from a payload, are returned unchecked.
+    pub fn verify(&self) -> Result<B256, Box<dyn Error>> {
+        let Some(input) = &self.input else {
+            return Ok(self.digest);
+        };
+        let digest = digest(input);
+        if digest != self.digest {
+            return Err(format!(
+                "Signing request claims digest {}, but its input's digest is {}",
+                self.digest, digest
+            )
+            .into());
+        }
+        if input.signer != self.signer {
+            return Err(format!(
+                "Signing request names signer {}, but its input's signer is {}",
+                self.signer.to_checksum(None),
+                input.signer.to_checksum(None)
+            )
+            .into());
+        }
+        Ok(digest)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("request serializes to JSON") + "\n"
+    }
+
+    /// Reads a request file, or the text payload of one as scanned from its QR code.
+    pub fn parse(content: &str) -> Result<Self, Box<dyn Error>> {
+        let content = content.trim();
+        let mut request = match content.strip_prefix(PAYLOAD_PREFIX) {
+            Some(query) => Self::from_payload(query)?,
+            None => {
+                let request = serde_json::from_str::<SigningRequest>(content)
+                    .map_err(|e| format!("Not a signing request: {}", e))?;
+                if request.input.is_none() {
+                    return Err("Signing request file has no input to check its digest".into());
+                }
+                request
+            }
+        };
+        if request.schema_version != REQUEST_SCHEMA_VERSION {
+            return Err(format!(
+                "Unsupported signing request version {}, expected {}",
+                request.schema_version, REQUEST_SCHEMA_VERSION
+            )
+            .into());
+        }
+        if request.sig_type != format!("{:?}", SignatureType::SECP256K1) {
+            return Err(format!("Cannot sign {} requests", request.sig_type).into());
+        }
+        request.verify()?;
+        if let Some(input) = &request.input {
+            // Never show a summary that was not rendered from what is signed
+            request.summary = render::text(input);
+        }
+        Ok(request)
+    }
+
+    fn from_payload(query: &str) -> Result<Self, Box<dyn Error>> {
+        let field = |name: &str| {
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+                .ok_or_else(|| format!("Signing request payload has no {}", name))
+        };
+        let module: Address = field("module")?.parse()?;
+        let summary = format!(
+            "title:         {}\nauditor:       {}\nmodule:        {}\n",
+            unescape(field("title")?)?,
+            unescape(field("auditor")?)?,
+            module.to_checksum(None)
+        );
+        Ok(SigningRequest {
+            schema_version: field("v")?.parse()?,
+            digest: field("digest")?.parse()?,
+            sig_type: field("sigType")?.to_string(),
+            signer: field("signer")?.parse()?,
+            input: None,
+            summary,
+            payload: format!("{}{}", PAYLOAD_PREFIX, query),
+        })
+    }
+}
+
+/// Percent-encodes everything but unreserved URI characters, so that text fits a payload field.
+fn escape(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            byte => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn unescape(text: &str) -> Result<String, Box<dyn Error>> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail
+                .get(..2)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or("Invalid escape in signing request payload")?;
+            bytes.push(hex);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    Ok(String::from_utf8(bytes)?)
+}
+
+fn digest(input: &Input) -> B256 {
+    input
+        .encode(SignatureType::SECP256K1, input.signer)
+        .signature
+        .hash
+}
+
+/// Reads the signature returned by the offline side: a detached `.sig` file, or the bare `0x` hex
+/// signature as scanned from its QR code.
+pub fn read_response(content: &str) -> Result<(Option<DetachedSignature>, Bytes), Box<dyn Error>> {
+    let content = content.trim();
+    if content.starts_with('{') {
+        let detached = DetachedSignature::from_json(content)?;
+        let signature = detached.signature.clone();
+        return Ok((Some(detached), signature));
+    }
+    let signature = from_hex_string(content).map_err(|e| format!("Invalid signature: {}", e))?;
+    Ok((None, Bytes::from(signature)))
+}
+
+/// `text` as a QR code drawn with Unicode blocks, for scanning off a terminal.
+pub fn qr(text: &str) -> Result<String, Box<dyn Error>> {
+    let code = QrCode::new(text.as_bytes())?;
+    Ok(code.render::<Dense1x2>().quiet_zone(true).build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::example;
+
+    #[test]
+    fn test_request_round_trips_through_json_and_payload() {
+        let request = SigningRequest::new(&example());
+        assert!(request.payload.starts_with(
+            "attestation:sign?v=2\
+             &digest=0x78062e31b9c55d8f1c52bf8a066946d76deedbc707bb41fcd90a2139c0e653b4\
+             &signer=0x05750cFD135858E346FA982d4Fa94E96053CDbc9&sigType=SECP256K1&title="
+        ));
+        assert!(request.summary.contains("Risk flags:"));
+        assert!(request
+            .to_json()
+            .contains("\"signer\": \"0x05750cFD135858E346FA982d4Fa94E96053CDbc9\""));
+
+        assert_eq!(SigningRequest::parse(&request.to_json()).unwrap(), request);
+        let scanned = SigningRequest::parse(&request.payload).unwrap();
+        assert_eq!(scanned.digest, request.digest);
+        assert_eq!(scanned.signer, request.signer);
+        assert!(scanned.input.is_none());
+        assert!(scanned
+            .summary
+            .contains(&format!("title:         {}\n", example().title)));
+
+        let truncated = request.payload.replace("&sigType=SECP256K1", "");
+        assert!(SigningRequest::parse(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_payload_does_not_grow_with_the_input() {
+        let mut input = example();
+        input.title = "Module & \"friends\" = 100%, naïve".to_string();
+        input.auditor.authors = (0..30).map(|n| format!("Auditor number {}", n)).collect();
+        let request = SigningRequest::new(&input);
+
+        assert!(request.payload.len() < 400, "{}", request.payload);
+        assert!(qr(&request.payload).is_ok());
+        let scanned = SigningRequest::parse(&request.payload).unwrap();
+        assert!(
+            scanned.summary.contains(&input.title),
+            "{}",
+            scanned.summary
+        );
+    }
+
+    #[test]
+    fn test_request_digest_must_match_its_input() {
+        let request = SigningRequest::new(&example());
+
+        // A summary edited to hide what is signed is replaced by one rendered from the input
+        let mut edited = request.clone();
+        edited.summary = "Nothing to see here\n".to_string();
+        assert_eq!(
+            SigningRequest::parse(&edited.to_json()).unwrap().summary,
+            request.summary
+        );
+
+        let mut tampered = request.clone();
+        tampered.input.as_mut().unwrap().title = "Another module".to_string();
+        let error = SigningRequest::parse(&tampered.to_json())
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("but its input's digest is"), "{}", error);
+
+        let mut tampered = request.clone();
+        tampered.digest = B256::repeat_byte(1);
+        assert!(SigningRequest::parse(&tampered.to_json()).is_err());
+
+        // Without its input, a request file could not be checked
+        let mut stripped = request.clone();
+        stripped.input = None;
+        assert!(SigningRequest::parse(&stripped.to_json()).is_err());
+    }
+
+    #[test]
+    fn test_read_response() {
+        let signature = "0xc0ae8c803180699e25668c1f55616ff422ba55cdcb8f4b2239a4a8030bd14ead\
+                         14e434aafe57a1e0e41f93ea770914667998b32896848b61100f10e7813045d21c";
+        let (detached, bytes) = read_response(&format!("{}\n", signature)).unwrap();
+        assert!(detached.is_none());
+        assert_eq!(bytes.len(), 65);
+
+        let sig = DetachedSignature::new(B256::ZERO, Address::ZERO, bytes.clone());
+        let (detached, from_file) = read_response(&sig.to_json()).unwrap();
+        assert_eq!(detached, Some(sig));
+        assert_eq!(from_file, bytes);
+
+        assert!(read_response("not a signature").is_err());
+    }
+}
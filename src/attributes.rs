@@ -0,0 +1,228 @@
+//! Plain-language descriptions of the attestation attributes, grouped like `moduleAttributes`.
+
+use crate::types::JsonModuleAttributes;
+
+pub struct Attribute {
+    pub key: &'static str,
+    pub description: &'static str,
+    /// Value that should be highlighted to reviewers, if any.
+    pub risky_when: Option<bool>,
+}
+
+pub struct AttributeGroup {
+    /// Key of the group in `moduleAttributes`
+    pub key: &'static str,
+    pub title: &'static str,
+    pub attributes: &'static [Attribute],
+}
+
+const fn attribute(key: &'static str, description: &'static str) -> Attribute {
+    Attribute {
+        key,
+        description,
+        risky_when: None,
+    }
+}
+
+const fn risky(key: &'static str, description: &'static str, risky_when: bool) -> Attribute {
+    Attribute {
+        key,
+        description,
+        risky_when: Some(risky_when),
+    }
+}
+
+pub const GROUPS: &[AttributeGroup] = &[
+    AttributeGroup {
+        key: "globalAttributes",
+        title: "Global attributes",
+        attributes: &[
+            attribute(
+                "reentrancyProtection",
+                "Guards state-changing entry points against reentrant calls.",
+            ),
+            attribute(
+                "importantDataValidation",
+                "Validates critical inputs such as addresses, amounts and lengths.",
+            ),
+            attribute(
+                "inputManipulationProtection",
+                "Protects against manipulated or malformed calldata.",
+            ),
+            attribute("emitsEvents", "Emits events for important state changes."),
+            risky(
+                "moduleOwnerCantRug",
+                "The module owner cannot seize funds or lock the account's configuration.",
+                false,
+            ),
+            risky(
+                "upgradeable",
+                "The module's code can be changed after it is installed.",
+                true,
+            ),
+            attribute("pausable", "An admin can pause the module."),
+            attribute("licensedModule", "The module is published under a license."),
+            attribute(
+                "erc7562StorageCompliant",
+                "Follows the ERC-7562 storage access rules for ERC-4337 validation.",
+            ),
+            attribute(
+                "uninstallCleanUp",
+                "Clears the module's account storage when it is uninstalled.",
+            ),
+            attribute(
+                "multichainCompatible",
+                "Can be deployed and used consistently across chains.",
+            ),
+        ],
+    },
+    AttributeGroup {
+        key: "validatorAttributes",
+        title: "Validator",
+        attributes: &[
+            attribute(
+                "unscopedValidator",
+                "Validates any call on behalf of the account, not only scoped actions.",
+            ),
+            attribute(
+                "recoveryModule",
+                "Can be used to recover access to the account.",
+            ),
+            attribute(
+                "multiplexer",
+                "Delegates validation to several underlying validators.",
+            ),
+        ],
+    },
+    AttributeGroup {
+        key: "executorAttributes",
+        title: "Executor",
+        attributes: &[
+            attribute(
+                "handlesUserAssets",
+                "Moves or manages assets held by the account.",
+            ),
+            risky(
+                "delegateCall",
+                "Runs code in the account's context via DELEGATECALL.",
+                true,
+            ),
+            attribute(
+                "triggeredByAccount",
+                "Executions are triggered by the account itself.",
+            ),
+            attribute(
+                "triggeredByRelayer",
+                "Executions can be triggered by an external relayer.",
+            ),
+            attribute(
+                "deterministicExecution",
+                "Executions have the same outcome for the same input.",
+            ),
+        ],
+    },
+    AttributeGroup {
+        key: "fallbackAttributes",
+        title: "Fallback handler",
+        attributes: &[
+            attribute(
+                "compatibilityFeature",
+                "Adds compatibility handlers such as token receiver callbacks.",
+            ),
+            attribute("callbacks", "Handles callbacks from external contracts."),
+        ],
+    },
+    AttributeGroup {
+        key: "hookAttributes",
+        title: "Hook",
+        attributes: &[
+            attribute(
+                "defaultAllow",
+                "Allows executions unless a rule denies them.",
+            ),
+            attribute(
+                "defaultDeny",
+                "Denies executions unless a rule allows them.",
+            ),
+            attribute("accessControl", "Enforces access control on executions."),
+            attribute(
+                "moduleControl",
+                "Restricts which modules can be installed or used.",
+            ),
+            attribute(
+                "userControl",
+                "Lets the account owner configure the hook's rules.",
+            ),
+        ],
+    },
+    AttributeGroup {
+        key: "externalDependency",
+        title: "External dependencies",
+        attributes: &[
+            attribute("oracle", "Relies on price or data oracles."),
+            attribute("bridges", "Relies on cross-chain bridges."),
+            attribute("dexs", "Trades on decentralized exchanges."),
+            attribute("vaults", "Deposits into or withdraws from vaults."),
+            attribute("registry", "Relies on an on-chain registry."),
+            attribute("lending", "Interacts with lending protocols."),
+            attribute(
+                "liquidityProvision",
+                "Provides liquidity to external protocols.",
+            ),
+            attribute(
+                "governance",
+                "Depends on governance decisions of external protocols.",
+            ),
+            attribute("privacy", "Interacts with privacy protocols."),
+            attribute("zkProvers", "Relies on zero-knowledge proof verifiers."),
+        ],
+    },
+];
+
+impl Attribute {
+    pub fn is_risky(&self, value: bool) -> bool {
+        self.risky_when == Some(value)
+    }
+}
+
+impl AttributeGroup {
+    /// Returns each attribute of the group with its value in `attributes`.
+    pub fn values(&self, attributes: &JsonModuleAttributes) -> Vec<(&'static Attribute, bool)> {
+        let value = serde_json::to_value(attributes).expect("module attributes serialize to JSON");
+        self.attributes
+            .iter()
+            .map(|attribute| {
+                let flag = value[self.key][attribute.key].as_bool().unwrap_or_default();
+                (attribute, flag)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::PackableAttributes;
+    use crate::types::{
+        JsonExecutorAttributes, JsonExternalDependency, JsonFallbackAttributes,
+        JsonGlobalAttributes, JsonHookAttributes, JsonValidatorAttributes,
+    };
+
+    #[test]
+    fn test_groups_cover_layouts() {
+        let layouts = [
+            JsonGlobalAttributes::LAYOUT,
+            JsonValidatorAttributes::LAYOUT,
+            JsonExecutorAttributes::LAYOUT,
+            JsonFallbackAttributes::LAYOUT,
+            JsonHookAttributes::LAYOUT,
+            JsonExternalDependency::LAYOUT,
+        ];
+
+        assert_eq!(GROUPS.len(), layouts.len());
+        for (group, layout) in GROUPS.iter().zip(layouts) {
+            let keys: Vec<&str> = group.attributes.iter().map(|a| a.key).collect();
+            assert_eq!(keys, layout, "{}", group.key);
+        }
+    }
+}
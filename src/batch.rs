@@ -0,0 +1,223 @@
+use alloy_primitives::B256;
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use attestation_rs::abi::{SignAttestation, SignatureType};
+
+use crate::files::{Output, SignedOutput};
+use crate::format::Format;
+use crate::signer::{sign_input, Signer};
+use crate::signing_log::SigningLog;
+use attestation_rs::profiles::AuditorProfiles;
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchStatus {
+    /// Validated and encoded, no signer was given
+    Encoded,
+    Signed,
+    Failed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchEntry {
+    pub file: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<PathBuf>,
+    pub digest: Option<B256>,
+    pub status: BatchStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Manifest {
+    pub files: Vec<BatchEntry>,
+}
+
+impl Manifest {
+    pub fn failed(&self) -> usize {
+        self.files
+            .iter()
+            .filter(|entry| entry.status == BatchStatus::Failed)
+            .count()
+    }
+}
+
+fn is_signed_output(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .is_some_and(|stem| stem.ends_with(".signed"))
+}
+
+/// Lists the JSON, YAML and TOML inputs in a directory, or the files matching a glob pattern.
+/// Signed outputs of previous runs are skipped.
+pub fn collect_inputs(pattern: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files: Vec<PathBuf> = if pattern.is_dir() {
+        fs::read_dir(pattern)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|path| Format::from_path(path).is_some())
+            .collect()
+    } else {
+        let pattern = pattern.to_str().ok_or("Batch pattern is not valid UTF-8")?;
+        glob::glob(pattern)?.collect::<Result<Vec<_>, _>>()?
+    };
+
+    files.retain(|path| path.is_file() && !is_signed_output(path));
+    files.sort();
+    Ok(files)
+}
+
+fn process_file(
+    path: &Path,
+    signer: Option<(&Signer, &SigningLog)>,
+    signed_output: &SignedOutput,
+    profiles: &AuditorProfiles,
+    force: bool,
+    entry: &mut BatchEntry,
+) -> Result<BatchStatus, Box<dyn Error>> {
+    let format = Format::from_path(path).unwrap_or(Format::Json);
+    let content = fs::read_to_string(path)?;
+    let mut input = format.parse_with(&content, profiles)?;
+    input.normalize_signature()?;
+    let summary = input.encode(SignatureType::SECP256K1, input.signer);
+    entry.digest = Some(summary.signature.hash);
+
+    let Some((signer, log)) = signer else {
+        return Ok(BatchStatus::Encoded);
+    };
+    let output = signed_output.path(path);
+    Output::File(output.clone()).check_writable(force)?;
+    let signed = sign_input(&input, signer)?;
+    log.append(
+        summary.signature.hash,
+        input.signer,
+        Some(&input),
+        content.as_bytes(),
+        signer.signer_type.name(),
+    )?;
+    let format = Format::from_path(&output).unwrap_or(format);
+    Output::File(output.clone()).write(&format.serialize(&signed)?, force)?;
+    entry.output = Some(output);
+
+    Ok(BatchStatus::Signed)
+}
+
+fn process(
+    path: &Path,
+    signer: Option<(&Signer, &SigningLog)>,
+    signed_output: &SignedOutput,
+    profiles: &AuditorProfiles,
+    force: bool,
+) -> BatchEntry {
+    let mut entry = BatchEntry {
+        file: path.to_path_buf(),
+        output: None,
+        digest: None,
+        status: BatchStatus::Failed,
+        error: None,
+    };
+    match process_file(path, signer, signed_output, profiles, force, &mut entry) {
+        Ok(status) => entry.status = status,
+        Err(e) => entry.error = Some(e.to_string()),
+    }
+    eprintln!("{:?}: {:?}", entry.file, entry.status);
+    entry
+}
+
+/// Validates, encodes and optionally signs every file. Failures are recorded in the manifest
+/// instead of aborting the run. Native signers run in parallel, prompting or hardware signers run
+/// one file at a time. Existing signed files are only replaced with `force`. Every signature is
+/// recorded in the signing log given with the signer.
+pub fn run(
+    files: &[PathBuf],
+    signer: Option<(&Signer, &SigningLog)>,
+    signed_output: &SignedOutput,
+    profiles: &AuditorProfiles,
+    force: bool,
+) -> Manifest {
+    if let Some(signer) = signer.filter(|(signer, _)| !signer.is_native()) {
+        return Manifest {
+            files: files
+                .iter()
+                .map(|file| process(file, Some(signer), signed_output, profiles, force))
+                .collect(),
+        };
+    }
+
+    let workers = thread::available_parallelism().map_or(1, |workers| workers.get());
+    let chunk_size = files.len().div_ceil(workers).max(1);
+    let files = thread::scope(|scope| {
+        let handles: Vec<_> = files
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|file| process(file, signer, signed_output, profiles, force))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("batch worker panicked"))
+            .collect()
+    });
+
+    Manifest { files }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secret::PrivateKeySource;
+    use crate::signer::SignerType;
+    use crate::fixtures::{scratch_dir, EXAMPLE_JSON, PRIVATE_KEY};
+
+    #[test]
+    fn test_failed_file_does_not_abort_batch() {
+        let dir = scratch_dir("batch");
+        fs::write(dir.join("a.json"), EXAMPLE_JSON).unwrap();
+        fs::write(dir.join("b.json"), "{}").unwrap();
+        fs::write(dir.join("a.signed.json"), "{}").unwrap();
+
+        let signer = Signer::from(SignerType::PrivateKey(PrivateKeySource {
+            private_key: Some(PRIVATE_KEY.to_string()),
+            ..Default::default()
+        }));
+        let files = collect_inputs(&dir).unwrap();
+        let log = SigningLog::new(dir.join("log").join("signing-log.jsonl"));
+        let manifest = run(
+            &files,
+            Some((&signer, &log)),
+            &SignedOutput::default(),
+            &AuditorProfiles::new(),
+            true,
+        );
+        let refused = run(
+            &files[..1],
+            Some((&signer, &log)),
+            &SignedOutput::default(),
+            &AuditorProfiles::new(),
+            false,
+        );
+        // The refused file was not signed, so it left no entry in the log
+        let verification = log.verify().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(verification.valid);
+        assert_eq!(verification.entries, 1);
+        assert_eq!(files, vec![dir.join("a.json"), dir.join("b.json")]);
+        assert_eq!(manifest.files[0].status, BatchStatus::Signed);
+        assert_eq!(manifest.files[0].output, Some(dir.join("a.signed.json")));
+        assert_eq!(manifest.files[1].status, BatchStatus::Failed);
+        assert_eq!(manifest.failed(), 1);
+        assert_eq!(refused.files[0].status, BatchStatus::Failed);
+    }
+}
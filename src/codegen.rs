@@ -0,0 +1,586 @@
+use std::error::Error;
+
+use crate::abi::{PackableAttributes, SCHEMA_SOURCE};
+use crate::types::{
+    JsonExecutorAttributes, JsonExternalDependency, JsonFallbackAttributes, JsonGlobalAttributes,
+    JsonHookAttributes, JsonValidatorAttributes,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SchemaField {
+    pub ty: String,
+    pub name: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SchemaItem {
+    Enum {
+        name: String,
+        variants: Vec<String>,
+    },
+    Struct {
+        name: String,
+        fields: Vec<SchemaField>,
+    },
+}
+
+impl SchemaItem {
+    pub fn name(&self) -> &str {
+        match self {
+            SchemaItem::Enum { name, .. } | SchemaItem::Struct { name, .. } => name,
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    for c in source.chars() {
+        if c.is_alphanumeric() || c == '_' || c == '$' {
+            current.push(c);
+            continue;
+        }
+        if !current.is_empty() {
+            tokens.push(std::mem::take(&mut current));
+        }
+        if !c.is_whitespace() {
+            tokens.push(c.to_string());
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Result<String, Box<dyn Error>> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or("Unexpected end of schema source")?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), Box<dyn Error>> {
+        let token = self.next()?;
+        if token != expected {
+            return Err(format!(
+                "Expected `{}` in schema source, found `{}`",
+                expected, token
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    fn skip_attribute(&mut self) -> Result<(), Box<dyn Error>> {
+        self.expect("#")?;
+        self.expect("[")?;
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next()?.as_str() {
+                "[" => depth += 1,
+                "]" => depth -= 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_enum(&mut self) -> Result<SchemaItem, Box<dyn Error>> {
+        let name = self.next()?;
+        self.expect("{")?;
+        let mut variants = Vec::new();
+        loop {
+            let token = self.next()?;
+            match token.as_str() {
+                "}" => break,
+                "," => continue,
+                _ => variants.push(token),
+            }
+        }
+        Ok(SchemaItem::Enum { name, variants })
+    }
+
+    fn parse_struct(&mut self) -> Result<SchemaItem, Box<dyn Error>> {
+        let name = self.next()?;
+        self.expect("{")?;
+        let mut fields = Vec::new();
+        while self.peek() != Some("}") {
+            let mut ty = self.next()?;
+            while self.peek() == Some("[") {
+                self.expect("[")?;
+                self.expect("]")?;
+                ty.push_str("[]");
+            }
+            let field_name = self.next()?;
+            self.expect(";")?;
+            fields.push(SchemaField {
+                ty,
+                name: field_name,
+            });
+        }
+        self.expect("}")?;
+        Ok(SchemaItem::Struct { name, fields })
+    }
+}
+
+/// Parses the enums and structs declared in the schema `sol!` block.
+pub fn parse_schema(source: &str) -> Result<Vec<SchemaItem>, Box<dyn Error>> {
+    let mut parser = Parser {
+        tokens: tokenize(source),
+        pos: 0,
+    };
+    let mut items = Vec::new();
+    while let Some(token) = parser.peek() {
+        match token {
+            "#" => parser.skip_attribute()?,
+            "enum" => {
+                parser.pos += 1;
+                items.push(parser.parse_enum()?);
+            }
+            "struct" => {
+                parser.pos += 1;
+                items.push(parser.parse_struct()?);
+            }
+            other => return Err(format!("Unsupported schema item `{}`", other).into()),
+        }
+    }
+    Ok(items)
+}
+
+pub fn schema_items() -> Vec<SchemaItem> {
+    parse_schema(SCHEMA_SOURCE).expect("schema sol! block is valid")
+}
+
+const SOLIDITY_HEADER: &str = "// SPDX-License-Identifier: UNLICENSED
+// This file is generated by `cargo run -- --mode codegen --lang solidity`. Do not edit by hand.
+pragma solidity ^0.8.13;
+
+import \"solady/utils/SignatureCheckerLib.sol\";
+import \"solady/utils/ECDSA.sol\";
+import \"solady/utils/Base64.sol\";
+";
+
+/// The `Schema` helper contract, appended as is after the generated types. Its functions are not
+/// generated, so changes to the schema that affect them are made in the template by hand.
+const SOLIDITY_CONTRACT: &str = include_str!("../templates/Schema.contract.sol");
+
+/// Renders `src/Schema.sol`: the schema types followed by the `Schema` helper contract from
+/// `templates/Schema.contract.sol`.
+pub fn solidity(items: &[SchemaItem]) -> String {
+    let mut out = String::from(SOLIDITY_HEADER);
+    for item in items {
+        out.push('\n');
+        match item {
+            SchemaItem::Enum { name, variants } => {
+                out.push_str(&format!("enum {} {{\n", name));
+                out.push_str(&format!("    {}\n", variants.join(",\n    ")));
+                out.push_str("}\n");
+            }
+            SchemaItem::Struct { name, fields } => {
+                out.push_str(&format!("struct {} {{\n", name));
+                for field in fields {
+                    out.push_str(&format!("    {} {};\n", field.ty, field.name));
+                }
+                out.push_str("}\n");
+            }
+        }
+    }
+    out.push('\n');
+    out.push_str(SOLIDITY_CONTRACT);
+    out
+}
+
+/// JSON shapes of `types::Input` and its nested structs that are not packed attribute sets.
+const TS_INTERFACES: &[(&str, &[(&str, &str)])] = &[
+    (
+        "Input",
+        &[
+            ("title", "string"),
+            ("auditor", "JsonAuditor"),
+            ("reportUrl", "string"),
+            ("signer", "Address"),
+            ("moduleAttributes", "JsonModuleAttributes"),
+            ("signature?", "JsonSignature | null"),
+        ],
+    ),
+    (
+        "JsonAuditor",
+        &[
+            ("name", "string"),
+            ("uri", "string"),
+            ("authors", "string[]"),
+        ],
+    ),
+    (
+        "JsonModuleAttributes",
+        &[
+            ("moduleAddress", "Address"),
+            ("globalAttributes", "JsonGlobalAttributes"),
+            ("validatorAttributes", "JsonValidatorAttributes"),
+            ("executorAttributes", "JsonExecutorAttributes"),
+            ("fallbackAttributes", "JsonFallbackAttributes"),
+            ("hookAttributes", "JsonHookAttributes"),
+            ("externalDependency", "JsonExternalDependency"),
+        ],
+    ),
+    (
+        "JsonSignature",
+        &[
+            ("hash", "Hex"),
+            ("signature", "Hex"),
+            ("sigType?", "\"SECP256K1\" | \"ERC1271\" | \"WEBAUTHN\""),
+        ],
+    ),
+];
+
+/// Interface name, packing layout and extra non-packed fields of a packed attribute set.
+type PackedInterface = (
+    &'static str,
+    &'static [&'static str],
+    &'static [(&'static str, &'static str)],
+);
+
+fn ts_packed_interfaces() -> Vec<PackedInterface> {
+    vec![
+        ("JsonGlobalAttributes", JsonGlobalAttributes::LAYOUT, &[]),
+        (
+            "JsonValidatorAttributes",
+            JsonValidatorAttributes::LAYOUT,
+            &[],
+        ),
+        (
+            "JsonExecutorAttributes",
+            JsonExecutorAttributes::LAYOUT,
+            &[],
+        ),
+        (
+            "JsonFallbackAttributes",
+            JsonFallbackAttributes::LAYOUT,
+            &[],
+        ),
+        ("JsonHookAttributes", JsonHookAttributes::LAYOUT, &[]),
+        (
+            "JsonExternalDependency",
+            JsonExternalDependency::LAYOUT,
+            &[("ercDeps", "number[]")],
+        ),
+    ]
+}
+
+/// Order of `ModuleAttributes.typeAttributes` as produced by `ParseAttributes::encode`.
+const TYPE_ATTRIBUTES: &[(&str, &str, &str)] = &[
+    (
+        "Validator",
+        "validatorAttributes",
+        "JsonValidatorAttributes",
+    ),
+    ("Executor", "executorAttributes", "JsonExecutorAttributes"),
+    ("Fallback", "fallbackAttributes", "JsonFallbackAttributes"),
+    ("Hook", "hookAttributes", "JsonHookAttributes"),
+];
+
+const TYPESCRIPT_HEADER: &str = "// This file is generated by `cargo run -- --mode codegen --lang typescript`. Do not edit by hand.
+
+export type Address = `0x${string}`;
+export type Hex = `0x${string}`;
+";
+
+const TYPESCRIPT_HELPERS: &str = "export function packAttributes<K extends string>(
+  layout: readonly K[],
+  attributes: Record<K, boolean>,
+): Hex {
+  return `0x${layout.map((key) => (attributes[key] ? \"01\" : \"00\")).join(\"\")}`;
+}
+
+export function unpackAttributes<K extends string>(
+  layout: readonly K[],
+  packed: Hex,
+): Record<K, boolean> {
+  const bytes = packed.slice(2);
+  if (bytes.length !== layout.length * 2) {
+    throw new Error(`Invalid byte length ${bytes.length / 2}, expected ${layout.length}`);
+  }
+  return Object.fromEntries(
+    layout.map((key, index) => [key, bytes.slice(index * 2, index * 2 + 2) !== \"00\"]),
+  ) as Record<K, boolean>;
+}
+";
+
+fn ts_abi_parameter(items: &[SchemaItem], ty: &str, name: &str, indent: &str) -> String {
+    let base = ty.trim_end_matches("[]");
+    let suffix = &ty[base.len()..];
+    let inner = format!("{}  ", indent);
+
+    match items.iter().find(|item| item.name() == base) {
+        Some(SchemaItem::Struct { fields, .. }) => {
+            let mut out = format!("{}{{\n", indent);
+            out.push_str(&format!("{}name: \"{}\",\n", inner, name));
+            out.push_str(&format!("{}type: \"tuple{}\",\n", inner, suffix));
+            out.push_str(&format!("{}internalType: \"struct {}\",\n", inner, ty));
+            out.push_str(&format!("{}components: [\n", inner));
+            for field in fields {
+                out.push_str(&ts_abi_parameter(
+                    items,
+                    &field.ty,
+                    &field.name,
+                    &format!("{}  ", inner),
+                ));
+            }
+            out.push_str(&format!("{}],\n{}}},\n", inner, indent));
+            out
+        }
+        Some(SchemaItem::Enum { .. }) => format!(
+            "{}{{ name: \"{}\", type: \"uint8{}\", internalType: \"enum {}\" }},\n",
+            indent, name, suffix, ty
+        ),
+        None => format!("{}{{ name: \"{}\", type: \"{}\" }},\n", indent, name, ty),
+    }
+}
+
+/// `JsonGlobalAttributes` -> `globalAttributesLayout`
+fn layout_name(interface: &str) -> String {
+    let name = interface.trim_start_matches("Json");
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => format!("{}{}Layout", first.to_lowercase(), chars.as_str()),
+        None => String::new(),
+    }
+}
+
+/// Renders TypeScript types for `Input`, viem ABI parameters for `AuditSummary`/`Digest` and the
+/// packed attribute layouts.
+pub fn typescript(items: &[SchemaItem]) -> String {
+    let mut out = String::from(TYPESCRIPT_HEADER);
+
+    for item in items {
+        if let SchemaItem::Enum { name, variants } = item {
+            out.push_str(&format!("\nexport const {} = {{\n", name));
+            for (index, variant) in variants.iter().enumerate() {
+                out.push_str(&format!("  {}: {},\n", variant, index));
+            }
+            out.push_str("} as const;\n");
+        }
+    }
+
+    for (name, fields) in TS_INTERFACES {
+        out.push_str(&format!("\nexport interface {} {{\n", name));
+        for (key, ty) in fields.iter() {
+            out.push_str(&format!("  {}: {};\n", key, ty));
+        }
+        out.push_str("}\n");
+    }
+
+    for (name, layout, extra) in ts_packed_interfaces() {
+        out.push_str(&format!("\nexport interface {} {{\n", name));
+        for key in layout {
+            out.push_str(&format!("  {}: boolean;\n", key));
+        }
+        for (key, ty) in extra {
+            out.push_str(&format!("  {}: {};\n", key, ty));
+        }
+        out.push_str("}\n");
+
+        out.push_str(&format!("\nexport const {} = [\n", layout_name(name)));
+        for key in layout {
+            out.push_str(&format!("  \"{}\",\n", key));
+        }
+        out.push_str("] as const;\n");
+    }
+
+    out.push_str("\nexport const typeAttributesLayout = [\n");
+    for (module_type, key, name) in TYPE_ATTRIBUTES {
+        out.push_str(&format!(
+            "  {{ moduleType: ERC7579ModuleType.{}, key: \"{}\", layout: {} }},\n",
+            module_type,
+            key,
+            layout_name(name)
+        ));
+    }
+    out.push_str("] as const;\n");
+
+    for (name, constant, parameter) in [
+        ("AuditSummary", "auditSummaryAbiParameters", "summary"),
+        ("Digest", "digestAbiParameters", "digest"),
+        (
+            "WebAuthnSignature",
+            "webAuthnSignatureAbiParameters",
+            "signature",
+        ),
+    ] {
+        out.push_str(&format!("\nexport const {} = [\n", constant));
+        out.push_str(&ts_abi_parameter(items, name, parameter, "  "));
+        out.push_str("] as const;\n");
+    }
+
+    out.push('\n');
+    out.push_str(TYPESCRIPT_HELPERS);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::ParseAttributes;
+    use crate::types::tests::example;
+    use crate::types::{JsonSignature, JsonSignatureType};
+    use alloy_primitives::Bytes;
+
+    #[test]
+    fn test_parse_schema() {
+        let items = parse_schema(
+            "#[derive(Debug)] enum Kind { None, Some } struct Foo { Kind kind; uint16 [] ids; }",
+        )
+        .unwrap();
+
+        assert_eq!(
+            items,
+            vec![
+                SchemaItem::Enum {
+                    name: "Kind".to_string(),
+                    variants: vec!["None".to_string(), "Some".to_string()],
+                },
+                SchemaItem::Struct {
+                    name: "Foo".to_string(),
+                    fields: vec![
+                        SchemaField {
+                            ty: "Kind".to_string(),
+                            name: "kind".to_string(),
+                        },
+                        SchemaField {
+                            ty: "uint16[]".to_string(),
+                            name: "ids".to_string(),
+                        },
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_module_attributes_include_erc_deps() {
+        let items = schema_items();
+        let fields = items
+            .iter()
+            .find_map(|item| match item {
+                SchemaItem::Struct { name, fields } if name == "ModuleAttributes" => Some(fields),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(fields.last().unwrap().ty, "uint16[]");
+        assert_eq!(fields.last().unwrap().name, "ercDeps");
+    }
+
+    fn object_keys(value: &serde_json::Value) -> Vec<String> {
+        value.as_object().unwrap().keys().cloned().collect()
+    }
+
+    fn interface_keys(name: &str) -> Vec<String> {
+        let mut keys: Vec<String> = TS_INTERFACES
+            .iter()
+            .find(|(interface, _)| *interface == name)
+            .map(|(_, fields)| {
+                fields
+                    .iter()
+                    .map(|(key, _)| key.trim_end_matches('?').to_string())
+                    .collect()
+            })
+            .unwrap_or_else(|| {
+                let (_, layout, extra) = ts_packed_interfaces()
+                    .into_iter()
+                    .find(|(interface, _, _)| *interface == name)
+                    .unwrap();
+                layout
+                    .iter()
+                    .chain(extra.iter().map(|(key, _)| key))
+                    .map(|key| key.to_string())
+                    .collect()
+            });
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn test_typescript_interfaces_match_json() {
+        let mut input = example();
+        input.signature = Some(JsonSignature {
+            hash: "0x00".to_string(),
+            signature: Bytes::default(),
+            sig_type: JsonSignatureType::Erc1271,
+        });
+        let value = serde_json::to_value(&input).unwrap();
+        let module = &value["moduleAttributes"];
+
+        assert_eq!(object_keys(&value), interface_keys("Input"));
+        assert_eq!(
+            object_keys(&value["auditor"]),
+            interface_keys("JsonAuditor")
+        );
+        assert_eq!(
+            object_keys(&value["signature"]),
+            interface_keys("JsonSignature")
+        );
+        assert_eq!(object_keys(module), interface_keys("JsonModuleAttributes"));
+        for (key, interface) in [
+            ("globalAttributes", "JsonGlobalAttributes"),
+            ("validatorAttributes", "JsonValidatorAttributes"),
+            ("executorAttributes", "JsonExecutorAttributes"),
+            ("fallbackAttributes", "JsonFallbackAttributes"),
+            ("hookAttributes", "JsonHookAttributes"),
+            ("externalDependency", "JsonExternalDependency"),
+        ] {
+            assert_eq!(object_keys(&module[key]), interface_keys(interface));
+        }
+    }
+
+    #[test]
+    fn test_type_attributes_order_matches_encode() {
+        let input = example();
+        let encoded = input.module_attributes.encode();
+
+        let module_types: Vec<String> = encoded
+            .typeAttributes
+            .iter()
+            .map(|attributes| format!("{:?}", attributes.moduleType))
+            .collect();
+        let expected: Vec<String> = TYPE_ATTRIBUTES
+            .iter()
+            .map(|(module_type, _, _)| module_type.to_string())
+            .collect();
+        assert_eq!(module_types, expected);
+    }
+
+    #[test]
+    fn test_committed_typescript_is_up_to_date() {
+        let committed = include_str!("../bindings/typescript/attestation.ts");
+        assert_eq!(
+            committed,
+            typescript(&schema_items()),
+            "bindings/typescript/attestation.ts is stale, run `cargo run -- --mode codegen --lang typescript --output bindings/typescript/attestation.ts`"
+        );
+    }
+
+    #[test]
+    fn test_committed_schema_is_up_to_date() {
+        let committed = include_str!("Schema.sol");
+        assert_eq!(
+            committed,
+            solidity(&schema_items()),
+            "src/Schema.sol is stale, run `cargo run -- --mode codegen --lang solidity --output src/Schema.sol`"
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use attestation_rs::types::JsonAuditor;
+
+use crate::signer::SignerType;
+
+/// Name of the per-project config file, looked up in the current directory only.
+pub const PROJECT_CONFIG: &str = "attestation.toml";
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Encoding {
+    /// Format of signed files written next to their input (json, yaml, toml)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Default for `--output-format` (text, json)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// Signer profile used when no signer is given on the command line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_signer: Option<String>,
+    /// Default auditor identity offered by `init`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auditor: Option<JsonAuditor>,
+    /// File with the auditor profiles attestations can reference by ID, relative to the config
+    /// file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auditor_profiles: Option<PathBuf>,
+    /// Directory signed files are written to instead of next to their input, relative to the
+    /// config file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_dir: Option<PathBuf>,
+    /// File every signature is recorded in, relative to the config file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_log: Option<PathBuf>,
+    /// Path to the `cast` binary used by the interactive, Ledger and Trezor signers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cast_path: Option<PathBuf>,
+    /// Seconds to wait for `cast`, a signer plugin or a remote signer before giving up, 0 to wait forever
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signer_timeout: Option<u64>,
+    #[serde(default)]
+    pub encoding: Encoding,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub signers: BTreeMap<String, SignerType>,
+}
+
+/// Directory of the user config, `$XDG_CONFIG_HOME/attestation` or `~/.config/attestation`.
+fn user_config_dir() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("attestation"))
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    Some(user_config_dir()?.join("config.toml"))
+}
+
+/// Default location of the signing log, next to the user config.
+pub fn default_signing_log() -> Option<PathBuf> {
+    Some(user_config_dir()?.join("signing-log.jsonl"))
+}
+
+fn project_config_path() -> Option<PathBuf> {
+    Some(env::current_dir().ok()?.join(PROJECT_CONFIG)).filter(|path| path.is_file())
+}
+
+impl Config {
+    pub fn parse(content: &str) -> Result<Config, Box<dyn Error>> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Reads the config at `path`, resolving relative directories against the file's location.
+    fn read(path: &Path) -> Result<Config, Box<dyn Error>> {
+        let mut config = Config::parse(&fs::read_to_string(path)?)
+            .map_err(|e| format!("Invalid config {:?}: {}", path, e))?;
+        if let Some(base) = path.parent() {
+            for dir in [
+                &mut config.output_dir,
+                &mut config.auditor_profiles,
+                &mut config.signing_log,
+            ] {
+                *dir = dir.as_ref().map(|dir| base.join(dir));
+            }
+            for signer in config.signers.values_mut() {
+                match signer {
+                    SignerType::PrivateKey(source) => source.relative_to(base),
+                    SignerType::Remote(options) => options.relative_to(base),
+                    _ => {}
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    /// Loads `explicit` if given, otherwise the user config overridden by the project config.
+    /// Returns the config with the files it was read from.
+    pub fn load(explicit: Option<&Path>) -> Result<(Config, Vec<PathBuf>), Box<dyn Error>> {
+        if let Some(path) = explicit {
+            return Ok((Config::read(path)?, vec![path.to_path_buf()]));
+        }
+
+        let mut config = Config::default();
+        let mut sources = Vec::new();
+        if let Some(path) = user_config_path().filter(|path| path.is_file()) {
+            config = config.merge(Config::read(&path)?);
+            sources.push(path);
+        }
+        if let Some(path) = project_config_path() {
+            let project = Config::read(&path)?;
+            project.check_project(&path)?;
+            config = config.merge(project);
+            sources.push(path);
+        }
+        Ok((config, sources))
+    }
+
+    /// Fails if the project config at `path` sets what a checked-out repository must not choose:
+    /// executables to run and where signatures are logged.
+    fn check_project(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut refused = Vec::new();
+        if self.cast_path.is_some() {
+            refused.push("cast-path".to_string());
+        }
+        if self.signing_log.is_some() {
+            refused.push("signing-log".to_string());
+        }
+        for (name, signer) in &self.signers {
+            match signer {
+                SignerType::Plugin { .. } => refused.push(format!("plugin signer {:?}", name)),
+                #[cfg(feature = "pkcs11")]
+                SignerType::Pkcs11(_) => refused.push(format!("pkcs11 signer {:?}", name)),
+                _ => {}
+            }
+        }
+        if refused.is_empty() {
+            return Ok(());
+        }
+        Err(format!(
+            "Project config {:?} sets {}, which only the user config or --config can set",
+            path,
+            refused.join(", ")
+        )
+        .into())
+    }
+
+    /// Overrides the settings of `self` with those set in `other`.
+    pub fn merge(mut self, other: Config) -> Config {
+        self.signers.extend(other.signers);
+        Config {
+            default_signer: other.default_signer.or(self.default_signer),
+            auditor: other.auditor.or(self.auditor),
+            auditor_profiles: other.auditor_profiles.or(self.auditor_profiles),
+            output_dir: other.output_dir.or(self.output_dir),
+            signing_log: other.signing_log.or(self.signing_log),
+            cast_path: other.cast_path.or(self.cast_path),
+            signer_timeout: other.signer_timeout.or(self.signer_timeout),
+            encoding: Encoding {
+                format: other.encoding.format.or(self.encoding.format),
+                output_format: other.encoding.output_format.or(self.encoding.output_format),
+            },
+            signers: self.signers,
+        }
+    }
+
+    pub fn signer(&self, profile: &str) -> Result<&SignerType, Box<dyn Error>> {
+        self.signers
+            .get(profile)
+            .ok_or_else(|| format!("Unknown signer profile: {}", profile).into())
+    }
+
+    /// Copy that is safe to print, with secrets replaced.
+    pub fn redacted(&self) -> Config {
+        let mut config = self.clone();
+        for signer in config.signers.values_mut() {
+            match signer {
+                SignerType::PrivateKey(source) => source.redact(),
+                SignerType::Remote(options) => options.redact(),
+                _ => {}
+            }
+        }
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"
+default-signer = "ci"
+output-dir = "attestation/signed"
+
+[auditor]
+name = "Ackee"
+uri = "http://audit.corp"
+authors = ["auditor1"]
+
+[encoding]
+format = "yaml"
+
+[signers.hardware]
+type = "ledger"
+
+[signers.ci]
+type = "private-key"
+private-key = "0x5ed8e65ce779308a499dfcb4b2d37a267847e084d80d1b51b09f0bf97e5b8319"
+
+[signers.service]
+type = "remote"
+url = "https://signer.audit.corp"
+api = "web3signer"
+headers = ["Authorization: Bearer 8f2e1c"]
+"#;
+
+    #[test]
+    fn test_parse_signer_profiles() {
+        let config = Config::parse(EXAMPLE).unwrap();
+
+        assert!(matches!(
+            config.signer("hardware").unwrap(),
+            SignerType::Ledger
+        ));
+        assert!(config.signer("ci").unwrap().is_native());
+        assert!(config.signer("missing").is_err());
+        assert_eq!(config.auditor.unwrap().name, "Ackee");
+        assert!(Config::parse("unknown = 1").is_err());
+    }
+
+    #[test]
+    fn test_project_overrides_user() {
+        let user = Config::parse(EXAMPLE).unwrap();
+        let project =
+            Config::parse("default-signer = \"hardware\"\n[encoding]\noutput-format = \"json\"")
+                .unwrap();
+
+        let config = user.merge(project);
+
+        assert_eq!(config.default_signer.as_deref(), Some("hardware"));
+        assert_eq!(config.encoding.format.as_deref(), Some("yaml"));
+        assert_eq!(config.encoding.output_format.as_deref(), Some("json"));
+        assert_eq!(config.signers.len(), 3);
+    }
+
+    #[test]
+    fn test_project_cannot_choose_executables_or_the_log() {
+        let path = Path::new(PROJECT_CONFIG);
+        assert!(Config::parse(EXAMPLE).unwrap().check_project(path).is_ok());
+
+        let project = Config::parse(
+            "cast-path = \"./cast\"\nsigning-log = \"log.jsonl\"\n\
+             [signers.hardware]\ntype = \"plugin\"\ncommand = \"./sign\"",
+        )
+        .unwrap();
+        let error = project.check_project(path).unwrap_err().to_string();
+        assert!(error.contains("\"attestation.toml\""), "{}", error);
+        assert!(
+            error.contains("cast-path, signing-log, plugin signer \"hardware\""),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_redacted_hides_private_keys() {
+        let shown = toml::to_string(&Config::parse(EXAMPLE).unwrap().redacted()).unwrap();
+
+        assert!(shown.contains("private-key = \"<redacted>\""));
+        assert!(!shown.contains("5ed8e65c"));
+        assert!(shown.contains("Authorization: <redacted>"));
+        assert!(!shown.contains("8f2e1c"));
+    }
+}
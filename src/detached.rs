@@ -0,0 +1,133 @@
+//! Signatures kept in a `.sig` file of their own next to an unsigned input, so that registries can
+//! mirror them separately and co-signatures can be added without rewriting the attestation.
+
+use alloy_primitives::{Address, Bytes, B256};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+use crate::abi::{SignAttestation, SignatureType};
+use crate::signature::{normalize_signature, recover_signer, Verification};
+use crate::types::{checksummed, Input};
+
+/// Version of the `.sig` file format written by this crate.
+pub const DETACHED_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DetachedSignature {
+    pub schema_version: u32,
+    /// Digest of the attestation that was signed
+    pub digest: B256,
+    pub sig_type: String,
+    #[serde(serialize_with = "checksummed")]
+    pub signer: Address,
+    pub signature: Bytes,
+}
+
+impl DetachedSignature {
+    /// A SECP256K1 signature by `signer` over `digest`.
+    pub fn new(digest: B256, signer: Address, signature: Bytes) -> Self {
+        DetachedSignature {
+            schema_version: DETACHED_SCHEMA_VERSION,
+            digest,
+            sig_type: format!("{:?}", SignatureType::SECP256K1),
+            signer,
+            signature,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("signature serializes to JSON") + "\n"
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        let detached: DetachedSignature = serde_json::from_str(json)?;
+        if detached.schema_version != DETACHED_SCHEMA_VERSION {
+            return Err(format!(
+                "Unsupported signature file version {}, expected {}",
+                detached.schema_version, DETACHED_SCHEMA_VERSION
+            )
+            .into());
+        }
+        Ok(detached)
+    }
+
+    /// Checks that this is a signature by `self.signer` over the digest of `input`. The signer
+    /// does not have to be the input's, so co-signatures verify too. Any signature embedded in
+    /// `input` is ignored.
+    pub fn verify(&self, input: &Input) -> Result<Verification, Box<dyn Error>> {
+        if self.sig_type != format!("{:?}", SignatureType::SECP256K1) {
+            return Err(format!("{} signatures cannot be verified offline", self.sig_type).into());
+        }
+
+        let digest = input
+            .encode(SignatureType::SECP256K1, input.signer)
+            .signature
+            .hash;
+        let normalized = normalize_signature(&self.signature)?;
+        let recovered = recover_signer(&digest, &normalized.signature)?;
+
+        Ok(Verification {
+            digest,
+            signer: self.signer,
+            recovered,
+            valid: self.digest == digest && recovered == self.signer,
+            normalized: normalized.applied,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::example;
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+
+    fn detach(input: &Input, key: &PrivateKeySigner) -> DetachedSignature {
+        let digest = input
+            .encode(SignatureType::SECP256K1, input.signer)
+            .signature
+            .hash;
+        let signature = key.sign_message_sync(digest.as_slice()).unwrap();
+        DetachedSignature::new(
+            digest,
+            key.address(),
+            Bytes::from(signature.as_bytes().to_vec()),
+        )
+    }
+
+    #[test]
+    fn test_detached_signatures_verify_against_input() {
+        let input = example();
+        let cosigner = PrivateKeySigner::random();
+        let detached = detach(&input, &cosigner);
+
+        let reparsed = DetachedSignature::from_json(&detached.to_json()).unwrap();
+        assert_eq!(reparsed, detached);
+        assert!(detached.verify(&input).unwrap().valid);
+
+        let mut edited = input.clone();
+        edited.title.push('!');
+        assert!(!detached.verify(&edited).unwrap().valid);
+
+        let mut forged = detached.clone();
+        forged.signer = input.signer;
+        let verification = forged.verify(&input).unwrap();
+        assert!(!verification.valid);
+        assert_eq!(verification.recovered, cosigner.address());
+    }
+
+    #[test]
+    fn test_rejects_unknown_versions_and_types() {
+        let input = example();
+        let mut detached = detach(&input, &PrivateKeySigner::random());
+
+        detached.schema_version = 2;
+        let error = DetachedSignature::from_json(&detached.to_json()).unwrap_err();
+        assert!(error.to_string().contains("version 2"), "{}", error);
+
+        detached.sig_type = "ERC1271".to_string();
+        assert!(detached.verify(&input).is_err());
+    }
+}
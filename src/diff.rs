@@ -0,0 +1,187 @@
+//! Attribute-by-attribute comparison of two attestations.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::attributes::GROUPS;
+use crate::types::Input;
+
+/// Keys whose changes mean the attestation is about a different module or from a different party.
+const IDENTITY_KEYS: &[&str] = &["auditor", "moduleAttributes.moduleAddress", "signer"];
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Flag {
+    /// Auditor, module address or signer changed
+    Identity,
+    /// An attribute changed to the value highlighted to reviewers
+    Risk,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Change {
+    /// Dotted path of the changed key, e.g. `moduleAttributes.globalAttributes.upgradeable`
+    pub key: String,
+    pub before: Value,
+    pub after: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flag: Option<Flag>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Diff {
+    pub changes: Vec<Change>,
+}
+
+fn flag(key: &str, after: &Value) -> Option<Flag> {
+    if IDENTITY_KEYS
+        .iter()
+        .any(|identity| key == *identity || key.starts_with(&format!("{}.", identity)))
+    {
+        return Some(Flag::Identity);
+    }
+
+    let (group, attribute) = key.strip_prefix("moduleAttributes.")?.split_once('.')?;
+    GROUPS
+        .iter()
+        .find(|g| g.key == group)?
+        .attributes
+        .iter()
+        .find(|a| a.key == attribute)
+        .filter(|a| after.as_bool().is_some_and(|value| a.is_risky(value)))
+        .map(|_| Flag::Risk)
+}
+
+fn compare(key: String, before: &Value, after: &Value, changes: &mut Vec<Change>) {
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            let added = after.keys().filter(|child| !before.contains_key(*child));
+            for child in before.keys().chain(added) {
+                let child_key = if key.is_empty() {
+                    child.clone()
+                } else {
+                    format!("{}.{}", key, child)
+                };
+                compare(
+                    child_key,
+                    before.get(child).unwrap_or(&Value::Null),
+                    after.get(child).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ if before != after => changes.push(Change {
+            flag: flag(&key, after),
+            key,
+            before: before.clone(),
+            after: after.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Compares everything but the signature, which changes whenever anything else does.
+pub fn diff(before: &Input, after: &Input) -> Diff {
+    let mut before = serde_json::to_value(before).expect("input serializes to JSON");
+    let mut after = serde_json::to_value(after).expect("input serializes to JSON");
+    for value in [&mut before, &mut after] {
+        value
+            .as_object_mut()
+            .expect("input serializes to a JSON object")
+            .remove("signature");
+    }
+
+    let mut changes = Vec::new();
+    compare(String::new(), &before, &after, &mut changes);
+    Diff { changes }
+}
+
+fn list_delta(before: &Value, after: &Value) -> Option<String> {
+    let (before, after) = (before.as_array()?, after.as_array()?);
+    let added = after
+        .iter()
+        .filter(|v| !before.contains(v))
+        .map(|v| format!("+{}", v));
+    let removed = before
+        .iter()
+        .filter(|v| !after.contains(v))
+        .map(|v| format!("-{}", v));
+    Some(added.chain(removed).collect::<Vec<_>>().join(", "))
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn text(&self) -> String {
+        if self.is_empty() {
+            return "No changes\n".to_string();
+        }
+        self.changes
+            .iter()
+            .map(|change| {
+                let marker = match change.flag {
+                    Some(Flag::Identity) => "! ",
+                    Some(Flag::Risk) => "⚠ ",
+                    None => "  ",
+                };
+                let delta = list_delta(&change.before, &change.after)
+                    .map(|delta| format!(" ({})", delta))
+                    .unwrap_or_default();
+                format!(
+                    "{}{}: {} -> {}{}\n",
+                    marker, change.key, change.before, change.after, delta
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::example;
+
+    #[test]
+    fn test_identical_inputs_have_no_changes() {
+        assert!(diff(&example(), &example()).is_empty());
+    }
+
+    #[test]
+    fn test_flags_risky_and_identity_changes() {
+        let before = example();
+        let mut after = example();
+        after.module_attributes.global_attributes.upgradeable = true;
+        after.module_attributes.global_attributes.pausable = true;
+        after
+            .module_attributes
+            .external_dependency
+            .erc_deps
+            .push(4626);
+        after.auditor.name = "Other".to_string();
+
+        let changes = diff(&before, &after).changes;
+        let mut flags: Vec<(&str, Option<Flag>)> = changes
+            .iter()
+            .map(|change| (change.key.as_str(), change.flag))
+            .collect();
+        flags.sort_by_key(|(key, _)| *key);
+
+        assert_eq!(
+            flags,
+            vec![
+                ("auditor.name", Some(Flag::Identity)),
+                ("moduleAttributes.externalDependency.ercDeps", None),
+                ("moduleAttributes.globalAttributes.pausable", None),
+                (
+                    "moduleAttributes.globalAttributes.upgradeable",
+                    Some(Flag::Risk)
+                ),
+            ]
+        );
+        assert!(diff(&before, &after)
+            .text()
+            .contains("ercDeps: [20,1155] -> [20,1155,4626] (+4626)"));
+    }
+}
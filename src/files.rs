@@ -0,0 +1,293 @@
+use alloy_primitives::Address;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::format::Format;
+
+/// Path argument that stands for stdin or stdout.
+const STDIO: &str = "-";
+
+pub fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == STDIO
+}
+
+/// Reads `path`, or stdin if it is `-`.
+pub fn read_to_string(path: &Path) -> io::Result<String> {
+    if is_stdio(path) {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+        return Ok(content);
+    }
+    fs::read_to_string(path)
+}
+
+/// Reads the raw bytes of `path`, or stdin if it is `-`.
+pub fn read(path: &Path) -> io::Result<Vec<u8>> {
+    if is_stdio(path) {
+        let mut content = Vec::new();
+        io::stdin().read_to_end(&mut content)?;
+        return Ok(content);
+    }
+    fs::read(path)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Output {
+    Stdout,
+    File(PathBuf),
+}
+
+impl From<&Path> for Output {
+    fn from(path: &Path) -> Self {
+        if is_stdio(path) {
+            Output::Stdout
+        } else {
+            Output::File(path.to_path_buf())
+        }
+    }
+}
+
+/// Where signed files are written when no explicit `--output` is given.
+#[derive(Debug, Clone, Default)]
+pub struct SignedOutput {
+    /// Directory to write to instead of the input's directory
+    pub dir: Option<PathBuf>,
+    /// Format to write instead of the input's format
+    pub format: Option<Format>,
+}
+
+impl SignedOutput {
+    /// Creates the output directory if one is configured.
+    pub fn create_dir(&self) -> io::Result<()> {
+        match &self.dir {
+            Some(dir) => fs::create_dir_all(dir),
+            None => Ok(()),
+        }
+    }
+
+    /// `<dir>/<stem>.signed.<extension>` for the input at `path`.
+    pub fn path(&self, path: &Path) -> PathBuf {
+        let file_stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        let extension = match self.format {
+            Some(format) => format.extension(),
+            None => path.extension().and_then(|s| s.to_str()).unwrap_or(""),
+        };
+
+        let new_file_stem = format!("{}.signed", file_stem);
+        let file_name = if extension.is_empty() {
+            new_file_stem
+        } else {
+            format!("{}.{}", new_file_stem, extension)
+        };
+
+        self.place(path, file_name)
+    }
+
+    /// `<dir>/<stem>.request.json` for a signing request for the input at `path`.
+    pub fn request_path(&self, path: &Path) -> PathBuf {
+        let file_stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        self.place(path, format!("{}.request.json", file_stem))
+    }
+
+    /// `<dir>/<stem>.sig` for a detached signature of the input at `path`, or
+    /// `<dir>/<stem>.<cosigner>.sig` for a co-signature, so that signatures by different signers
+    /// sit side by side.
+    pub fn detached_path(&self, path: &Path, cosigner: Option<Address>) -> PathBuf {
+        let file_stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        let file_name = match cosigner {
+            Some(address) => format!("{}.{}.sig", file_stem, address.to_checksum(None)),
+            None => format!("{}.sig", file_stem),
+        };
+        self.place(path, file_name)
+    }
+
+    /// `<dir>/<stem>.<owner>.safe.sig` for the signature of a Safe owner over the `SafeMessage`
+    /// hash of the input at `path`, apart from signatures over the input's digest.
+    pub fn owner_signature_path(&self, path: &Path, owner: Address) -> PathBuf {
+        let file_stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        self.place(
+            path,
+            format!("{}.{}.safe.sig", file_stem, owner.to_checksum(None)),
+        )
+    }
+
+    /// `file_name` in the output directory, or next to the input at `path`.
+    fn place(&self, path: &Path, file_name: String) -> PathBuf {
+        match &self.dir {
+            Some(dir) => dir.join(file_name),
+            None => path.with_file_name(file_name),
+        }
+    }
+}
+
+/// Makes temporary file names unique between writes of the same process, e.g. `batch` workers.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Writes `content` to a temporary file next to `path` and moves it into place, so readers never
+/// observe a partially written file. Unless `replace` is set, the file is published with a hard
+/// link, which fails instead of replacing a file that appeared in the meantime.
+fn write_atomic(path: &Path, content: &[u8], replace: bool) -> io::Result<()> {
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "Output path has no file name")
+    })?;
+    let tmp = dir.join(format!(
+        ".{}.{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&tmp)
+        .and_then(|mut file| {
+            file.write_all(content)?;
+            file.sync_all()
+        })
+        .and_then(|_| {
+            if replace {
+                fs::rename(&tmp, path)
+            } else {
+                fs::hard_link(&tmp, path)
+            }
+        });
+    // After a rename there is nothing left to remove
+    let _ = fs::remove_file(&tmp);
+    result
+}
+
+fn already_exists(path: &Path) -> Box<dyn Error> {
+    format!("{:?} already exists, pass --force to overwrite", path).into()
+}
+
+impl Output {
+    /// Fails if `write` would refuse to replace an existing file, so that it can be checked before
+    /// signing. `write` still refuses a file that appears in between.
+    pub fn check_writable(&self, force: bool) -> Result<(), Box<dyn Error>> {
+        match self {
+            Output::File(path) if !force && path.exists() => Err(already_exists(path)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes `content`, refusing to replace an existing file unless `force` is set.
+    pub fn write(&self, content: &str, force: bool) -> Result<(), Box<dyn Error>> {
+        match self {
+            Output::Stdout => {
+                let mut stdout = io::stdout().lock();
+                stdout.write_all(content.as_bytes())?;
+                stdout.flush()?;
+            }
+            Output::File(path) => match write_atomic(path, content.as_bytes(), force) {
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    return Err(already_exists(path));
+                }
+                result => result?,
+            },
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::scratch_dir;
+
+    #[test]
+    fn test_write_refuses_overwrite_without_force() {
+        let path = scratch_dir("files").join("out.json");
+        let output = Output::File(path.clone());
+
+        output.check_writable(false).unwrap();
+        output.write("first", false).unwrap();
+        assert!(output.check_writable(false).is_err());
+        assert!(output.check_writable(true).is_ok());
+        assert!(output.write("second", false).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+
+        output.write("second", true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_writes_do_not_clobber() {
+        let path = scratch_dir("files-race").join("out.json");
+
+        let written: Vec<String> = std::thread::scope(|scope| {
+            let writers: Vec<_> = (0..8)
+                .map(|n| {
+                    let output = Output::File(path.clone());
+                    scope.spawn(move || {
+                        output
+                            .write(&n.to_string(), false)
+                            .ok()
+                            .map(|_| n.to_string())
+                    })
+                })
+                .collect();
+            writers
+                .into_iter()
+                .filter_map(|writer| writer.join().unwrap())
+                .collect()
+        });
+        assert_eq!(written.len(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), written[0]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_signed_output_path() {
+        let input = Path::new("attestation/example.json");
+
+        assert_eq!(
+            SignedOutput::default().path(input),
+            PathBuf::from("attestation/example.signed.json")
+        );
+        let configured = SignedOutput {
+            dir: Some(PathBuf::from("signed")),
+            format: Some(Format::Yaml),
+        };
+        assert_eq!(
+            configured.path(input),
+            PathBuf::from("signed/example.signed.yaml")
+        );
+
+        assert_eq!(
+            SignedOutput::default().detached_path(input, None),
+            PathBuf::from("attestation/example.sig")
+        );
+        assert_eq!(
+            configured.request_path(input),
+            PathBuf::from("signed/example.request.json")
+        );
+        assert_eq!(
+            configured.detached_path(input, Some(Address::repeat_byte(0xab))),
+            PathBuf::from("signed/example.0xABaBaBaBABabABabAbAbABAbABabababaBaBABaB.sig")
+        );
+        assert_eq!(
+            SignedOutput::default().owner_signature_path(input, Address::repeat_byte(0xab)),
+            PathBuf::from(
+                "attestation/example.0xABaBaBaBABabABabAbAbABAbABabababaBaBABaB.safe.sig"
+            )
+        );
+    }
+
+    #[test]
+    fn test_dash_is_stdout() {
+        assert_eq!(Output::from(Path::new("-")), Output::Stdout);
+        assert_eq!(
+            Output::from(Path::new("out.json")),
+            Output::File(PathBuf::from("out.json"))
+        );
+    }
+}
@@ -0,0 +1,23 @@
+//! Fixtures shared by the unit tests of the binary. The library's are in `types::tests`.
+
+use std::fs;
+use std::path::PathBuf;
+
+use attestation_rs::types::Input;
+
+/// Key of the example attestation's signer, `0x05750cFD135858E346FA982d4Fa94E96053CDbc9`.
+pub const PRIVATE_KEY: &str = "0x5ed8e65ce779308a499dfcb4b2d37a267847e084d80d1b51b09f0bf97e5b8319";
+
+pub const EXAMPLE_JSON: &str = include_str!("../attestation/example.json");
+
+pub fn example() -> Input {
+    serde_json::from_str(EXAMPLE_JSON).unwrap()
+}
+
+/// An empty directory for the test `name`, unique to this process.
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("attestation-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,179 @@
+use serde::de::DeserializeOwned;
+use std::error::Error;
+use std::path::Path;
+use std::str::FromStr;
+
+use attestation_rs::profiles::{AuditorProfiles, ProfiledInput};
+use attestation_rs::types::Input;
+
+/// Serialization formats accepted for input files. They all map to the same `Input`, so the digest
+/// does not depend on the source format.
+///
+/// YAML reads an unquoted `0x…` as a hex integer when it fits in one, as for
+/// `0x000000000000000000000000000000000000dEaD`. Such addresses fail to parse with an error naming
+/// the field and asking to quote them. Files written by `convert` quote them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl FromStr for Format {
+    type Err = Box<dyn Error>;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "json" => Ok(Format::Json),
+            "yaml" | "yml" => Ok(Format::Yaml),
+            "toml" => Ok(Format::Toml),
+            format => Err(format!("Unsupported input format: {}", format).into()),
+        }
+    }
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Yaml => "yaml",
+            Format::Toml => "toml",
+        }
+    }
+
+    /// Guesses the format from the file extension.
+    pub fn from_path(path: &Path) -> Option<Format> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+
+    /// Uses the explicit `--format` if given, then the extension of `path`, then JSON.
+    pub fn resolve(explicit: Option<&str>, path: &Path) -> Result<Format, Box<dyn Error>> {
+        match explicit {
+            Some(format) => format.parse(),
+            None => Ok(Format::from_path(path).unwrap_or(Format::Json)),
+        }
+    }
+
+    pub fn deserialize<T: DeserializeOwned>(self, content: &str) -> Result<T, Box<dyn Error>> {
+        Ok(match self {
+            Format::Json => serde_json::from_str(content)?,
+            Format::Yaml => serde_yaml::from_str(content)?,
+            Format::Toml => toml::from_str(content)?,
+        })
+    }
+
+    /// Parses an input that may reference one of `profiles` as its auditor.
+    pub fn parse_with(
+        self,
+        content: &str,
+        profiles: &AuditorProfiles,
+    ) -> Result<Input, Box<dyn Error>> {
+        self.deserialize::<ProfiledInput>(content)?
+            .resolve(profiles)
+    }
+
+    /// Serializes `input`, using the canonical form for JSON.
+    pub fn serialize(self, input: &Input) -> Result<String, Box<dyn Error>> {
+        Ok(match self {
+            Format::Json => input.to_canonical_json(),
+            Format::Yaml => serde_yaml::to_string(input)?,
+            Format::Toml => toml::to_string(input)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::EXAMPLE_JSON;
+    use attestation_rs::abi::{SignAttestation, SignatureType};
+
+    impl Format {
+        fn parse(self, content: &str) -> Result<Input, Box<dyn Error>> {
+            self.parse_with(content, &AuditorProfiles::new())
+        }
+    }
+
+    #[test]
+    fn test_digest_does_not_depend_on_format() {
+        let input = Format::Json.parse(EXAMPLE_JSON).unwrap();
+        let digest = |input: &Input| {
+            input
+                .encode(SignatureType::SECP256K1, input.signer)
+                .signature
+                .hash
+        };
+
+        for format in [Format::Yaml, Format::Toml] {
+            let serialized = format.serialize(&input).unwrap();
+            let converted = format.parse(&serialized).unwrap();
+            assert_eq!(converted, input);
+            assert_eq!(digest(&converted), digest(&input));
+        }
+    }
+
+    #[test]
+    fn test_yaml_comments_and_converted_addresses() {
+        let yaml = Format::Yaml
+            .serialize(&Format::Json.parse(EXAMPLE_JSON).unwrap())
+            .unwrap()
+            .replace(
+                "moduleOwnerCantRug: false",
+                "moduleOwnerCantRug: false # owner can replace the implementation",
+            );
+
+        assert!(yaml.contains("signer: 0x05750cFD135858E346FA982d4Fa94E96053CDbc9\n"));
+        let input = Format::Yaml.parse(&yaml).unwrap();
+        assert!(
+            !input
+                .module_attributes
+                .global_attributes
+                .module_owner_cant_rug
+        );
+        assert_eq!(
+            input.signer.to_checksum(None),
+            "0x05750cFD135858E346FA982d4Fa94E96053CDbc9"
+        );
+
+        let dead = "0x000000000000000000000000000000000000dEaD";
+        let quoted = yaml.replace(
+            "signer: 0x05750cFD135858E346FA982d4Fa94E96053CDbc9",
+            &format!("signer: '{}'", dead),
+        );
+        assert_eq!(
+            Format::Yaml
+                .parse(&quoted)
+                .unwrap()
+                .signer
+                .to_checksum(None),
+            dead
+        );
+        let unquoted = quoted.replace(&format!("'{}'", dead), dead);
+        let error = Format::Yaml.parse(&unquoted).unwrap_err().to_string();
+        assert!(error.starts_with("signer: "), "{}", error);
+        assert!(error.contains("quote it"), "{}", error);
+    }
+
+    #[test]
+    fn test_yaml_numeric_title_is_a_string() {
+        let input = Format::Json.parse(EXAMPLE_JSON).unwrap();
+        let yaml = Format::Yaml
+            .serialize(&input)
+            .unwrap()
+            .replace(&format!("title: {}\n", input.title), "title: 2024\n");
+
+        assert!(yaml.contains("title: 2024\n"));
+        assert_eq!(Format::Yaml.parse(&yaml).unwrap().title, "2024");
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(Format::from_path(Path::new("a.yml")), Some(Format::Yaml));
+        assert_eq!(Format::from_path(Path::new("a.toml")), Some(Format::Toml));
+        assert_eq!(Format::resolve(None, Path::new("-")).unwrap(), Format::Json);
+        assert_eq!(
+            Format::resolve(Some("toml"), Path::new("a.json")).unwrap(),
+            Format::Toml
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use alloy_primitives::Address;
+use serde_json::{json, Map, Value};
+use std::error::Error;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use attestation_rs::attributes::GROUPS;
+use attestation_rs::types::{Input, JsonAuditor};
+
+/// Groups that only apply if the module implements the corresponding ERC-7579 module type.
+const TYPE_GROUPS: &[&str] = &[
+    "validatorAttributes",
+    "executorAttributes",
+    "fallbackAttributes",
+    "hookAttributes",
+];
+
+struct Prompter<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    fn line(&mut self, prompt: &str) -> Result<String, Box<dyn Error>> {
+        write!(self.output, "{}: ", prompt)?;
+        self.output.flush()?;
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Err("Unexpected end of input".into());
+        }
+        Ok(line.trim().to_string())
+    }
+
+    fn text(&mut self, prompt: &str) -> Result<String, Box<dyn Error>> {
+        self.text_or(prompt, None)
+    }
+
+    /// Prompts for a non-empty value, or `default` if the answer is empty.
+    fn text_or(&mut self, prompt: &str, default: Option<&str>) -> Result<String, Box<dyn Error>> {
+        let prompt = match default {
+            Some(default) => format!("{} [{}]", prompt, default),
+            None => prompt.to_string(),
+        };
+        loop {
+            let line = self.line(&prompt)?;
+            if !line.is_empty() {
+                return Ok(line);
+            }
+            if let Some(default) = default {
+                return Ok(default.to_string());
+            }
+            writeln!(self.output, "  a value is required")?;
+        }
+    }
+
+    fn list(&mut self, prompt: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        self.list_or(prompt, &[])
+    }
+
+    fn list_or(&mut self, prompt: &str, default: &[String]) -> Result<Vec<String>, Box<dyn Error>> {
+        let prompt = if default.is_empty() {
+            format!("{} (comma separated)", prompt)
+        } else {
+            format!("{} (comma separated) [{}]", prompt, default.join(", "))
+        };
+        let line = self.line(&prompt)?;
+        if line.is_empty() {
+            return Ok(default.to_vec());
+        }
+        Ok(line
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    fn parsed<T: FromStr>(&mut self, prompt: &str) -> Result<T, Box<dyn Error>>
+    where
+        T::Err: std::fmt::Display,
+    {
+        loop {
+            match self.text(prompt)?.parse() {
+                Ok(value) => return Ok(value),
+                Err(e) => writeln!(self.output, "  invalid value: {}", e)?,
+            }
+        }
+    }
+
+    fn erc_deps(&mut self) -> Result<Vec<u16>, Box<dyn Error>> {
+        loop {
+            let parsed = self
+                .list("ERCs the module depends on, e.g. 20, 721")?
+                .iter()
+                .map(|erc| erc.trim_start_matches("ERC-").parse::<u16>())
+                .collect::<Result<Vec<_>, _>>();
+            match parsed {
+                Ok(deps) => return Ok(deps),
+                Err(e) => writeln!(self.output, "  invalid ERC number: {}", e)?,
+            }
+        }
+    }
+
+    fn yes_no(&mut self, prompt: &str) -> Result<bool, Box<dyn Error>> {
+        loop {
+            match self
+                .line(&format!("{} [y/N]", prompt))?
+                .to_lowercase()
+                .as_str()
+            {
+                "y" | "yes" => return Ok(true),
+                "" | "n" | "no" => return Ok(false),
+                _ => writeln!(self.output, "  please answer y or n")?,
+            }
+        }
+    }
+}
+
+/// Builds a new `Input` from answers read from `input`, writing the prompts to `output`. Empty
+/// answers for the auditor fall back to the configured `auditor`.
+pub fn run<R: BufRead, W: Write>(
+    input: R,
+    output: W,
+    auditor: Option<&JsonAuditor>,
+) -> Result<Input, Box<dyn Error>> {
+    let mut prompter = Prompter { input, output };
+
+    let title = prompter.text("Module title")?;
+    let auditor_name = prompter.text_or("Auditor name", auditor.map(|a| a.name.as_str()))?;
+    let auditor_uri = prompter.text_or("Auditor URI", auditor.map(|a| a.uri.as_str()))?;
+    let authors = prompter.list_or(
+        "Report authors",
+        auditor.map_or(&[], |a| a.authors.as_slice()),
+    )?;
+    let report_url = prompter.text("Report URL")?;
+    let module_address: Address = prompter.parsed("Module address")?;
+    let signer: Address = prompter.parsed("Signer address")?;
+
+    let mut module_attributes = Map::new();
+    module_attributes.insert("moduleAddress".to_string(), json!(module_address));
+    for group in GROUPS {
+        let implemented = !TYPE_GROUPS.contains(&group.key)
+            || prompter.yes_no(&format!("Is the module a {}?", group.title.to_lowercase()))?;
+        if implemented {
+            writeln!(prompter.output, "\n{}", group.title)?;
+        }
+
+        let mut values = Map::new();
+        for attribute in group.attributes {
+            let value = implemented
+                && prompter.yes_no(&format!("  {} - {}", attribute.key, attribute.description))?;
+            values.insert(attribute.key.to_string(), Value::Bool(value));
+        }
+        if group.key == "externalDependency" {
+            values.insert("ercDeps".to_string(), json!(prompter.erc_deps()?));
+        }
+        module_attributes.insert(group.key.to_string(), Value::Object(values));
+    }
+
+    Ok(serde_json::from_value(json!({
+        "title": title,
+        "auditor": {
+            "name": auditor_name,
+            "uri": auditor_uri,
+            "authors": authors,
+        },
+        "reportUrl": report_url,
+        "signer": signer,
+        "moduleAttributes": module_attributes,
+        "signature": null,
+    }))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skips_unimplemented_module_types() {
+        let answers = [
+            "FoobarModule",
+            "Ackee",
+            "http://audit.corp",
+            "auditor1, auditor2",
+            "http://audit.corp/report",
+            "not an address",
+            "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+            "0x05750cFD135858E346FA982d4Fa94E96053CDbc9",
+        ]
+        .into_iter()
+        // Global attributes: only reentrancyProtection
+        .chain(["y"].into_iter().chain(["n"; 10]))
+        // Validator, with an invalid answer first
+        .chain(["maybe", "yes", "y", "n", "n"])
+        // Not an executor, fallback handler or hook
+        .chain(["", "no", "n"])
+        // External dependencies: only an oracle
+        .chain(["y"].into_iter().chain([""; 9]))
+        .chain(["ERC-20, 1155"])
+        .collect::<Vec<_>>()
+        .join("\n");
+
+        let mut prompts = Vec::new();
+        let input = run(answers.as_bytes(), &mut prompts, None).unwrap();
+        let attributes = &input.module_attributes;
+
+        assert_eq!(input.auditor.authors, vec!["auditor1", "auditor2"]);
+        assert!(attributes.global_attributes.reentrancy_protection);
+        assert!(!attributes.global_attributes.emits_events);
+        assert!(attributes.validator_attributes.unscoped_validator);
+        assert!(!attributes.executor_attributes.handles_user_assets);
+        assert!(attributes.external_dependency.oracle);
+        assert_eq!(attributes.external_dependency.erc_deps, vec![20, 1155]);
+        assert!(String::from_utf8(prompts)
+            .unwrap()
+            .contains("reentrancyProtection - Guards state-changing"));
+    }
+
+    #[test]
+    fn test_end_of_input_is_an_error() {
+        assert!(run("FoobarModule\n".as_bytes(), Vec::new(), None).is_err());
+    }
+
+    #[test]
+    fn test_configured_auditor_is_the_default() {
+        let auditor = JsonAuditor {
+            name: "Ackee".to_string(),
+            uri: "http://audit.corp".to_string(),
+            authors: vec!["auditor1".to_string()],
+        };
+        let answers = "FoobarModule\n\n\n\nhttp://audit.corp/report\n\
+            0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48\n\
+            0x05750cFD135858E346FA982d4Fa94E96053CDbc9\n"
+            .to_string()
+            + &"\n".repeat(40);
+
+        let input = run(answers.as_bytes(), Vec::new(), Some(&auditor)).unwrap();
+
+        assert_eq!(input.auditor, auditor);
+    }
+}
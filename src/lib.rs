@@ -0,0 +1,15 @@
+pub mod abi;
+pub mod attributes;
+pub mod codegen;
+pub mod detached;
+pub mod diff;
+pub mod profiles;
+pub mod render;
+pub mod safe;
+pub mod signature;
+pub mod types;
+pub mod webauthn;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "python")]
+pub mod python;
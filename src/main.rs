@@ -1,102 +1,932 @@
 use alloy_primitives::{Address, Bytes};
 use alloy_sol_types::SolValue;
-use clap::{Parser, Subcommand};
+use clap::Parser;
 
+use std::fs;
 use std::path::PathBuf;
-use std::{fs, str::FromStr};
-mod abi;
-mod types;
-use crate::abi::{HashAuditSummary, PackedSig, ParseAttributes, SignAttestation, SignatureType};
-use crate::types::{Input, JsonSignature};
-use alloy_signer::{Signer, SignerSync};
-use alloy_signer_local::PrivateKeySigner;
-use serde_json;
-use std::fs::File;
-use std::io::Write;
-use std::path::Path;
-use std::process::{exit, Command};
-
-
-#[derive(Subcommand, Debug)]
-#[command(author, version, about, long_about = None)]
-enum SignerType {
-    /// Interactive mode
-    Interactive,
-    Ledger,
-    Trezor,
 
-    /// Private key mode
-    PrivateKey {
-        #[arg(short, long)]
-        private_key: String,
-    },
-}
+mod airgap;
+mod batch;
+mod config;
+mod files;
+mod format;
+mod init;
+#[cfg(test)]
+mod mock_signer;
+#[cfg(feature = "pkcs11")]
+mod pkcs11;
+mod plugin;
+mod remote;
+mod report;
+mod secret;
+mod signer;
+mod signing_log;
+#[cfg(test)]
+mod fixtures;
+use crate::airgap::SigningRequest;
+use crate::config::Config;
+use crate::files::{Output, SignedOutput};
+use crate::format::Format;
+use crate::plugin::SignerOptions;
+use crate::report::{DetachedStatus, Report};
+use crate::signer::{
+    attach_signature, from_hex_string, sign_detached, sign_input, sign_safe_hash, Signer,
+    SignerType,
+};
+use crate::signing_log::SigningLog;
+use attestation_rs::abi::{decode_summary, SignAttestation, SignatureType, WebAuthnSignature};
+use attestation_rs::detached::DetachedSignature;
+use attestation_rs::profiles::AuditorProfiles;
+use attestation_rs::render::VerificationStatus;
+use attestation_rs::safe::{self, OwnerSignature, SafeOwners};
+use attestation_rs::types::{Input, JsonSignature, JsonSignatureType};
+use attestation_rs::{codegen, diff, render, webauthn};
+use std::path::Path;
+use std::process::exit;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Path to the JSON input file
-    #[arg(short, long)]
-    input: PathBuf,
+    /// Path to the input file (`-` for stdin), or a directory or glob pattern in `batch` mode
     #[arg(short, long)]
-    private_key: Option<String>,
+    input: Option<PathBuf>,
+    /// Format of the input file (json, yaml, toml), defaults to its extension
+    #[arg(long)]
+    format: Option<String>,
+    /// Target format for `convert` mode (json, yaml, toml), defaults to the `--output` extension
+    #[arg(long)]
+    to: Option<String>,
     #[arg(short, long)]
     mode: String,
+    /// Target language for `codegen` (solidity, typescript) and `render` (markdown, html) modes
+    #[arg(long)]
+    lang: Option<String>,
+    /// Path to write the signed input, generated output or `batch` manifest to (`-` for stdout)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Overwrite existing signed files
+    #[arg(long)]
+    force: bool,
+    /// Fail instead of writing if the file at `--output` differs from the generated output, or the
+    /// input is not canonical in `fmt` mode
+    #[arg(long)]
+    check: bool,
+    /// Write the signature to a detached `.sig` file instead of a signed copy of the input in
+    /// `cast` mode
+    #[arg(long)]
+    detached: bool,
+    /// Address to sign as with `--detached`, for co-signing an input with another signer
+    #[arg(long, requires = "detached")]
+    sign_as: Option<Address>,
+    /// Detached `.sig` file to check in `verify` mode instead of the input's own signature, can be
+    /// repeated. In `finalize` mode, the signature returned for a signing request, as a `.sig`
+    /// file or `0x` hex. In `passkey` mode, the credential JSON of the passkey assertion
+    #[arg(long = "signature")]
+    signatures: Vec<PathBuf>,
+    /// Chain ID of the Safe in `safe-hash` and `safe` modes, and when verifying Safe signatures
+    #[arg(long)]
+    chain_id: Option<u64>,
+    /// Address of the Safe signing the attestation, defaults to the input's signer
+    #[arg(long)]
+    safe: Option<Address>,
+    /// File with the Safe's `owners` and `threshold`, to assemble and verify owner signatures
+    #[arg(long)]
+    safe_owners: Option<PathBuf>,
+    /// Owner of the Safe signing its `SafeMessage` hash in `safe-owner-sign` mode
+    #[arg(long)]
+    owner: Option<Address>,
+    /// Public key of the passkey in `passkey` mode, as the hex SPKI that `getPublicKey()` returns
+    /// at registration or an uncompressed SEC1 point
+    #[arg(long, value_parser = <Bytes as std::str::FromStr>::from_str)]
+    public_key: Option<Bytes>,
+    /// Attestation to compare `--input` with in `diff` mode
+    #[arg(long)]
+    against: Option<PathBuf>,
+    /// Print results as text or as a JSON object on stdout. Human-readable messages always go to
+    /// stderr, and `diff` and `batch` print their JSON result as is. Defaults to text.
+    #[arg(long, value_parser = ["text", "json"])]
+    output_format: Option<String>,
+    /// Config file to use instead of the user and project `attestation.toml`
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Signer profile from the config file, used if no signer subcommand is given
+    #[arg(long)]
+    signer: Option<String>,
+    /// Directory to write signed files to instead of next to their input
+    #[arg(long)]
+    output_dir: Option<PathBuf>,
+    /// File with auditor profiles that inputs can reference by ID as their `auditor`
+    #[arg(long)]
+    auditor_profiles: Option<PathBuf>,
+    /// File every signature is recorded in, and that `log-verify` checks (default
+    /// `~/.config/attestation/signing-log.jsonl`)
+    #[arg(long)]
+    signing_log: Option<PathBuf>,
+    /// Path to the `cast` binary used by the interactive, Ledger and Trezor signers
+    #[arg(long)]
+    cast_path: Option<PathBuf>,
+    /// Seconds to wait for `cast`, a signer plugin or a remote signer before giving up, 0 to wait
+    /// forever (default 300)
+    #[arg(long)]
+    signer_timeout: Option<u64>,
     #[command(subcommand)]
     signer_type: Option<SignerType>,
 }
-fn to_hex_string(bytes: &[u8]) -> String {
-    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
-    format!("0x{}", hex)
+
+impl Args {
+    fn json(&self) -> bool {
+        self.output_format.as_deref() == Some("json")
+    }
+
+    /// Fills the settings not given on the command line from `config`.
+    fn apply(&mut self, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+        if self.output_format.is_none() {
+            self.output_format = config.encoding.output_format.clone();
+        }
+        if let Some(format) = self.output_format.as_deref() {
+            if format != "text" && format != "json" {
+                return Err(format!("Unsupported output format: {}", format).into());
+            }
+        }
+        if self.signer.is_none() {
+            self.signer = config.default_signer.clone();
+        }
+        if self.signer_type.is_none() {
+            if let Some(profile) = &self.signer {
+                self.signer_type = Some(config.signer(profile)?.clone());
+            }
+        }
+        if self.output_dir.is_none() {
+            self.output_dir = config.output_dir.clone();
+        }
+        if self.auditor_profiles.is_none() {
+            self.auditor_profiles = config.auditor_profiles.clone();
+        }
+        if self.signing_log.is_none() {
+            self.signing_log = config.signing_log.clone();
+        }
+        if self.cast_path.is_none() {
+            self.cast_path = config.cast_path.clone();
+        }
+        if self.signer_timeout.is_none() {
+            self.signer_timeout = config.signer_timeout;
+        }
+        Ok(())
+    }
+
+    /// The signer given on the command line or by profile, with its private key already read.
+    fn signer(&self) -> Result<Option<Signer>, Box<dyn std::error::Error>> {
+        let defaults = SignerOptions::default();
+        let options = SignerOptions {
+            cast_path: self.cast_path.clone().unwrap_or(defaults.cast_path),
+            timeout: match self.signer_timeout {
+                Some(0) => None,
+                Some(seconds) => Some(std::time::Duration::from_secs(seconds)),
+                None => defaults.timeout,
+            },
+        };
+        let Some(signer_type) = self.signer_type.clone() else {
+            return Ok(None);
+        };
+        let mut signer = Signer::from(signer_type);
+        signer.options = options;
+        signer.unlock().map(Some)
+    }
+
+    fn signing_log(&self) -> Result<SigningLog, Box<dyn std::error::Error>> {
+        self.signing_log
+            .clone()
+            .or_else(config::default_signing_log)
+            .map(SigningLog::new)
+            .ok_or_else(|| "Set --signing-log, there is no home directory to keep it in".into())
+    }
+
+    fn load_profiles(&self) -> Result<AuditorProfiles, Box<dyn std::error::Error>> {
+        let Some(path) = &self.auditor_profiles else {
+            return Ok(AuditorProfiles::new());
+        };
+        let format = Format::from_path(path).unwrap_or(Format::Toml);
+        format
+            .deserialize(&fs::read_to_string(path)?)
+            .map_err(|e| format!("Invalid auditor profiles {:?}: {}", path, e).into())
+    }
+
+    /// Address and chain of the Safe signing `input`.
+    fn safe(&self, input: &Input) -> Result<(Address, u64), Box<dyn std::error::Error>> {
+        let chain_id = self
+            .chain_id
+            .ok_or("--chain-id is required for Safe signatures")?;
+        Ok((self.safe.unwrap_or(input.signer), chain_id))
+    }
+
+    fn load_safe_owners(&self) -> Result<SafeOwners, Box<dyn std::error::Error>> {
+        let path = self
+            .safe_owners
+            .as_deref()
+            .ok_or("--safe-owners is required for Safe signatures")?;
+        let format = Format::from_path(path).unwrap_or(Format::Toml);
+        format
+            .deserialize(&fs::read_to_string(path)?)
+            .map_err(|e| format!("Invalid Safe owners {:?}: {}", path, e).into())
+    }
+
+    fn outfile(&self) -> Output {
+        self.output.as_deref().map_or(Output::Stdout, Output::from)
+    }
+}
+
+/// Prints the effective settings: the config files merged, with command line flags applied.
+fn config_show(
+    args: &Args,
+    config: &Config,
+    sources: &[PathBuf],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut effective = config.redacted();
+    effective.default_signer = args.signer.clone();
+    effective.output_dir = args.output_dir.clone();
+    effective.auditor_profiles = args.auditor_profiles.clone();
+    effective.cast_path = args.cast_path.clone();
+    effective.signer_timeout = args.signer_timeout;
+    effective.signing_log = args.signing_log.clone();
+    effective.encoding.output_format = args.output_format.clone();
+
+    if args.json() {
+        let shown = serde_json::json!({ "sources": sources, "config": effective });
+        println!("{}", serde_json::to_string_pretty(&shown)?);
+    } else {
+        for source in sources {
+            println!("# {}", source.display());
+        }
+        print!("{}", toml::to_string(&effective)?);
+    }
+    Ok(())
+}
+
+fn codegen(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let items = codegen::schema_items();
+    let generated = match args.lang.as_deref().unwrap_or("solidity") {
+        "solidity" => codegen::solidity(&items),
+        "typescript" => codegen::typescript(&items),
+        lang => return Err(format!("Unsupported codegen language: {}", lang).into()),
+    };
+
+    match &args.output {
+        Some(path) if args.check => {
+            let committed = fs::read_to_string(path)?;
+            if committed != generated {
+                eprintln!("{:?} is out of date, rerun codegen without --check", path);
+                exit(1);
+            }
+        }
+        Some(_) => write_document(args, &args.outfile(), &generated, true)?,
+        None if args.check => return Err("--check requires --output".into()),
+        None => write_document(args, &Output::Stdout, &generated, true)?,
+    }
+
+    Ok(())
+}
+
+/// Writes the document produced by a mode. With `--output-format json` the document must go to a
+/// file, and a report naming it is printed instead.
+fn write_document(
+    args: &Args,
+    outfile: &Output,
+    content: &str,
+    force: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if args.json() && *outfile == Output::Stdout {
+        return Err(format!(
+            "--output-format json requires --output in {} mode",
+            args.mode
+        )
+        .into());
+    }
+    outfile.write(content, force)?;
+    if args.json() {
+        Report::new(&args.mode).written_to(outfile).emit()?;
+    }
+    Ok(())
 }
-fn from_hex_string(hex_string: &str) -> Result<Vec<u8>, hex::FromHexError> {
-    // Remove "0x" prefix if present
-    let cleaned_hex = hex_string.strip_prefix("0x").unwrap_or(hex_string);
-    
-    // Use the hex crate to decode the string
-    hex::decode(cleaned_hex)
+
+fn write_output(args: &Args, content: &str) -> Result<(), Box<dyn std::error::Error>> {
+    write_document(args, &args.outfile(), content, true)
 }
 
-fn to_outfile(path_buf: &PathBuf) -> PathBuf {
-    let file_stem = path_buf.file_stem().and_then(|s| s.to_str()).unwrap_or("");
-    let extension = path_buf.extension().and_then(|s| s.to_str()).unwrap_or("");
+/// Format to write `outfile` in: its extension, or `fallback` for stdout and unknown extensions.
+fn output_format(outfile: &Output, fallback: Format) -> Format {
+    match outfile {
+        Output::File(path) => Format::from_path(path).unwrap_or(fallback),
+        Output::Stdout => fallback,
+    }
+}
+
+/// Loads an input file, or an ABI-encoded `AuditSummary` as raw bytes or `0x` hex as printed by
+/// `bytes` mode. Returns whether the attestation was ABI-encoded.
+fn load_attestation(
+    path: &Path,
+    profiles: &AuditorProfiles,
+) -> Result<(Input, bool), Box<dyn std::error::Error>> {
+    let content = files::read(path)?;
+    let text = std::str::from_utf8(&content).map(str::trim);
+    if let (Ok(text), Some(format @ (Format::Yaml | Format::Toml))) =
+        (text, Format::from_path(path))
+    {
+        return Ok((format.parse_with(text, profiles)?, false));
+    }
 
-    let new_file_stem = format!("{}.signed", file_stem);
+    let data = match text {
+        Ok(text) if text.starts_with('{') => {
+            return Ok((Format::Json.parse_with(text, profiles)?, false))
+        }
+        Ok(text) if text.starts_with("0x") => from_hex_string(text)?,
+        _ => content,
+    };
+    let summary = decode_summary(&data)
+        .map_err(|e| format!("{:?} is not an input file or AuditSummary: {}", path, e))?;
+    Ok((*Input::decode(&summary)?, true))
+}
 
-    if extension.is_empty() {
-        path_buf.with_file_name(new_file_stem)
+fn diff(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let profiles = args.load_profiles()?;
+    let (mut before, before_encoded) = load_attestation(
+        args.input.as_deref().ok_or("--input is required")?,
+        &profiles,
+    )?;
+    let (mut after, after_encoded) = load_attestation(
+        args.against.as_deref().ok_or("--against is required")?,
+        &profiles,
+    )?;
+    if before_encoded || after_encoded {
+        eprintln!("reportUrl is not part of the encoded AuditSummary and is not compared");
+        before.report_url.clear();
+        after.report_url.clear();
+    }
+    let diff = diff::diff(&before, &after);
+
+    let rendered = if args.json() {
+        serde_json::to_string_pretty(&diff)? + "\n"
+    } else {
+        diff.text()
+    };
+    args.outfile().write(&rendered, true)
+}
+
+/// Decodes an attestation, typically an ABI-encoded `AuditSummary`, into an input file with a
+/// canonical signature.
+fn decode(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut input, _) = load_attestation(
+        args.input.as_deref().ok_or("--input is required")?,
+        &args.load_profiles()?,
+    )?;
+    report::normalize(&mut input)?;
+    let outfile = args.outfile();
+    let format = output_format(&outfile, Format::Json);
+    write_document(args, &outfile, &format.serialize(&input)?, args.force)
+}
+
+/// Checks the signature of an input file or ABI-encoded `AuditSummary`, or the detached signatures
+/// given with `--signature`, exiting with 1 unless all are valid.
+fn verify(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut input, _) = load_attestation(
+        args.input.as_deref().ok_or("--input is required")?,
+        &args.load_profiles()?,
+    )?;
+    if !args.signatures.is_empty() {
+        return verify_detached(args, &input);
+    }
+    if input
+        .signature
+        .as_ref()
+        .is_some_and(|signature| signature.sig_type == JsonSignatureType::Erc1271)
+    {
+        return verify_safe(args, &input);
+    }
+    let normalized = report::normalize(&mut input)?;
+    let status = VerificationStatus::of(&input);
+    let valid = matches!(status, VerificationStatus::Valid);
+
+    if args.json() {
+        let mut report = Report::for_input(&args.mode, &input).normalized(normalized);
+        report.valid = Some(valid);
+        report.emit()?;
     } else {
-        path_buf.with_file_name(format!("{}.{}", new_file_stem, extension))
+        println!("signature {}", status.describe());
+    }
+    if !valid {
+        exit(1);
     }
+    Ok(())
 }
 
-fn add_signed_to_filename(path: &Path) -> PathBuf {
-    let file_stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
-    let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+/// Checks the owner signatures of an input signed by a Safe against its owner list and threshold.
+fn verify_safe(args: &Args, input: &Input) -> Result<(), Box<dyn std::error::Error>> {
+    let (safe, chain_id) = args.safe(input)?;
+    if input.signer != safe {
+        return Err(format!(
+            "the input's signer {} is not the Safe {}",
+            input.signer.to_checksum(None),
+            safe.to_checksum(None)
+        )
+        .into());
+    }
+    let owners = args.load_safe_owners()?;
+    let summary = input.encode(SignatureType::ERC1271, input.signer);
+    let signature = input.signature.as_ref().ok_or("the input is unsigned")?;
+    let digest = summary.signature.hash;
+    let verification = safe::verify(safe, chain_id, &digest, &signature.signature, &owners);
+
+    let (valid, description) = match &verification {
+        Ok(verification) if verification.valid => (
+            true,
+            format!(
+                "valid, {} of {} required owners signed",
+                verification.signers.len(),
+                verification.threshold
+            ),
+        ),
+        Ok(verification) => (
+            false,
+            format!(
+                "INVALID, {} of {} required owners signed",
+                verification.signers.len(),
+                verification.threshold
+            ),
+        ),
+        Err(e) => (false, format!("could not be verified: {}", e)),
+    };
+
+    if args.json() {
+        let mut report = Report::new(&args.mode);
+        report.digest = Some(digest);
+        report.encoded = Some(Bytes::from(summary.abi_encode()));
+        report.signer = Some(safe.to_checksum(None));
+        report.signature = Some(signature.signature.clone());
+        report.safe_hash = Some(safe::safe_message_hash(safe, chain_id, &digest));
+        report.valid = Some(valid);
+        if !valid {
+            report.warnings.push(description);
+        }
+        report.emit()?;
+    } else {
+        println!("signature {}", description);
+    }
+    if !valid {
+        exit(1);
+    }
+    Ok(())
+}
 
-    let new_file_stem = format!("{}.signed", file_stem);
+/// Collects owner signatures over the `SafeMessage` hash into a signed input with an ERC-1271
+/// signature by the Safe, failing unless the owner threshold is met.
+fn safe_sign(args: &Args, input: &Input) -> Result<Input, Box<dyn std::error::Error>> {
+    let (safe, chain_id) = args.safe(input)?;
+    let owners = args.load_safe_owners()?;
+    let digest = input
+        .encode(SignatureType::ERC1271, input.signer)
+        .signature
+        .hash;
+    let safe_hash = safe::safe_message_hash(safe, chain_id, &digest);
+    if args.signatures.is_empty() {
+        return Err("safe mode requires the owners' --signature files".into());
+    }
+
+    let mut signatures = Vec::new();
+    for path in &args.signatures {
+        let (detached, signature) = airgap::read_response(&files::read_to_string(path)?)?;
+        if let Some(detached) = detached {
+            if detached.digest != safe_hash {
+                return Err(format!(
+                    "{:?} signs {}, but the SafeMessage hash is {}",
+                    path, detached.digest, safe_hash
+                )
+                .into());
+            }
+        }
+        let owner_signature = OwnerSignature::new(&safe_hash, &signature, &owners.owners)
+            .map_err(|e| format!("{:?}: {}", path, e))?;
+        eprintln!(
+            "{:?}: signed by owner {}",
+            path,
+            owner_signature.owner.to_checksum(None)
+        );
+        signatures.push(owner_signature);
+    }
 
-    let mut new_path = path.with_file_name(new_file_stem);
-    if !extension.is_empty() {
-        new_path.set_extension(extension);
+    let assembled = safe::assemble(signatures);
+    let verification = safe::verify(safe, chain_id, &digest, &assembled, &owners)?;
+    if !verification.valid {
+        return Err(format!(
+            "{} of {} required owners signed",
+            verification.signers.len(),
+            verification.threshold
+        )
+        .into());
     }
 
-    new_path
+    if input.signer != safe {
+        eprintln!("signer set to the Safe {}", safe.to_checksum(None));
+    }
+    let mut signed = input.clone();
+    signed.signer = safe;
+    signed.signature = Some(JsonSignature {
+        hash: digest.to_string(),
+        signature: assembled,
+        sig_type: JsonSignatureType::Erc1271,
+    });
+    Ok(signed)
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
+/// Attaches a passkey assertion over the input's digest, as the credential JSON of
+/// `navigator.credentials.get()`, as a `WEBAUTHN` signature by the passkey's address.
+fn attach_passkey(args: &Args, input: &Input) -> Result<Input, Box<dyn std::error::Error>> {
+    let [path] = args.signatures.as_slice() else {
+        return Err("passkey mode requires exactly one --signature".into());
+    };
+    let public_key = args
+        .public_key
+        .as_ref()
+        .ok_or("passkey mode requires the passkey's --public-key")?;
+    let envelope =
+        WebAuthnSignature::from_credential_json(&files::read_to_string(path)?, public_key)
+            .map_err(|e| format!("{:?}: {}", path, e))?;
+    let signature = Bytes::from(envelope.abi_encode());
+    let digest = input
+        .encode(SignatureType::WEBAUTHN, input.signer)
+        .signature
+        .hash;
+    let (signer, valid) = webauthn::verify(&digest, &signature)?;
+    if !valid {
+        return Err(format!(
+            "{:?} is not a user-present assertion by that key over the input's digest {}",
+            path, digest
+        )
+        .into());
+    }
 
-    let input_path = args.input.clone();
-    // Read the JSON file
-    let json_input = fs::read_to_string(args.input)?;
-    // let private_key = args.private_key;
+    if input.signer != signer {
+        eprintln!("signer set to the passkey {}", signer.to_checksum(None));
+    }
+    let mut signed = input.clone();
+    signed.signer = signer;
+    signed.signature = Some(JsonSignature {
+        hash: digest.to_string(),
+        signature,
+        sig_type: JsonSignatureType::WebAuthn,
+    });
+    Ok(signed)
+}
 
-    let input: Input = serde_json::from_str(&json_input)?;
-    // let signer = PrivateKeySigner::from_str(&private_key)?;
-    let mut onchain_data = input.encode(SignatureType::SECP256K1, Address::default());
+fn verify_detached(args: &Args, input: &Input) -> Result<(), Box<dyn std::error::Error>> {
+    let statuses: Vec<DetachedStatus> = args
+        .signatures
+        .iter()
+        .map(|path| {
+            let checked = fs::read_to_string(path)
+                .map_err(|e| e.into())
+                .and_then(|json| DetachedSignature::from_json(&json))
+                .and_then(|detached| Ok((detached.verify(input)?, detached.digest)));
+            match checked {
+                Ok((verification, signed_digest)) => DetachedStatus {
+                    path: path.clone(),
+                    signer: Some(verification.signer.to_checksum(None)),
+                    valid: verification.valid,
+                    error: if verification.valid {
+                        None
+                    } else if signed_digest != verification.digest {
+                        Some(format!(
+                            "signed digest {} is not the input's digest {}",
+                            signed_digest, verification.digest
+                        ))
+                    } else {
+                        Some(format!(
+                            "signed by {}",
+                            verification.recovered.to_checksum(None)
+                        ))
+                    },
+                },
+                Err(e) => DetachedStatus {
+                    path: path.clone(),
+                    signer: None,
+                    valid: false,
+                    error: Some(e.to_string()),
+                },
+            }
+        })
+        .collect();
+    let valid = statuses.iter().all(|status| status.valid);
+
+    if args.json() {
+        let mut report = Report::for_input(&args.mode, input);
+        report.signatures = statuses;
+        report.valid = Some(valid);
+        report.emit()?;
+    } else {
+        for status in &statuses {
+            match (&status.signer, &status.error) {
+                (Some(signer), None) => println!("{:?}: valid, signed by {}", status.path, signer),
+                (_, Some(error)) => println!("{:?}: INVALID, {}", status.path, error),
+                (None, None) => unreachable!("failed checks carry an error"),
+            }
+        }
+    }
+    if !valid {
+        exit(1);
+    }
+    Ok(())
+}
+
+/// Where to write a file produced from the input at `input_path`: the explicit `--output`, stdout
+/// when the input came from stdin, or `default` next to the input or in the configured output
+/// directory.
+fn signed_outfile(
+    args: &Args,
+    input_path: &Path,
+    signed_output: &SignedOutput,
+    default: impl FnOnce(&SignedOutput) -> PathBuf,
+) -> Result<Output, Box<dyn std::error::Error>> {
+    let outfile = match &args.output {
+        Some(path) => Output::from(path.as_path()),
+        None if files::is_stdio(input_path) => Output::Stdout,
+        None => {
+            signed_output.create_dir()?;
+            Output::File(default(signed_output))
+        }
+    };
+    if args.json() && outfile == Output::Stdout {
+        return Err(format!(
+            "--output-format json requires --output for stdin in {} mode",
+            args.mode
+        )
+        .into());
+    }
+    Ok(outfile)
+}
+
+/// Writes a signed input in the format of `outfile`, or `format` for stdout.
+fn write_signed(
+    args: &Args,
+    signed: &Input,
+    outfile: &Output,
+    format: Format,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let output_format = output_format(outfile, format);
+    outfile.write(&output_format.serialize(signed)?, args.force)?;
+    if let Output::File(path) = outfile {
+        eprintln!("written to: {:?}", path);
+    }
+    if args.json() {
+        Report::for_input(&args.mode, signed)
+            .written_to(outfile)
+            .emit()?;
+    }
+    Ok(())
+}
+
+/// Signs a request prepared by `prepare` on another machine, writing the detached signature and
+/// showing it as a QR code to carry back.
+fn sign_request(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let path = args.input.as_deref().ok_or("--input is required")?;
+    let content = files::read_to_string(path)?;
+    let request = SigningRequest::parse(&content)?;
+    eprint!("{}", request.summary);
+    if request.input.is_none() {
+        eprintln!(
+            "warning: a scanned payload's summary is not covered by the digest, \
+             compare the digest with the one prepare printed"
+        );
+    }
+    eprintln!("signing hash:  {}", request.digest);
+    eprintln!("signer:        {}", request.signer.to_checksum(None));
+
+    let outfile = args.outfile();
+    if args.json() && outfile == Output::Stdout {
+        return Err("--output-format json requires --output in sign-request mode".into());
+    }
+    outfile.check_writable(args.force)?;
+
+    let signer = args.signer()?.ok_or("sign-request needs a signer")?;
+    let detached = signer::sign_request(&request, &signer)?;
+    args.signing_log()?.append(
+        detached.digest,
+        detached.signer,
+        request.input.as_ref(),
+        content.as_bytes(),
+        signer.signer_type.name(),
+    )?;
+    eprint!("{}", airgap::qr(&detached.signature.to_string())?);
+
+    outfile.write(&detached.to_json(), args.force)?;
+    if args.json() {
+        let mut report = Report::new(&args.mode).written_to(&outfile);
+        report.digest = Some(detached.digest);
+        report.signer = Some(detached.signer.to_checksum(None));
+        report.signature = Some(detached.signature);
+        report.emit()?;
+    }
+    Ok(())
+}
+
+/// Checks that the signing log has not been edited or truncated since it was written.
+fn log_verify(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let verification = args.signing_log()?.verify()?;
+    let valid = verification.valid;
+    if args.json() {
+        let mut report = Report::new(&args.mode);
+        report.valid = Some(valid);
+        report.log = Some(verification);
+        report.emit()?;
+    } else if let Some(error) = &verification.error {
+        println!(
+            "{:?}: INVALID after {} intact entries, {}",
+            verification.path, verification.entries, error
+        );
+    } else {
+        println!(
+            "{:?}: valid, {} entries, head {}",
+            verification.path, verification.entries, verification.head
+        );
+    }
+    if !valid {
+        exit(1);
+    }
+    Ok(())
+}
+
+/// Signs `input` into a detached `.sig` file next to it, leaving the input file untouched.
+fn cast_detached(
+    args: &Args,
+    input: &Input,
+    raw_input: &str,
+    signer: &Signer,
+    outfile: &Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let address = args.sign_as.unwrap_or(input.signer);
+    let detached = sign_detached(input, signer, address)?;
+    args.signing_log()?.append(
+        detached.digest,
+        address,
+        Some(input),
+        raw_input.as_bytes(),
+        signer.signer_type.name(),
+    )?;
+    write_detached(args, input, &detached, outfile, |_| {})
+}
+
+/// Signs the `SafeMessage` hash of the input's digest as the Safe's `owner`, into a detached
+/// signature that `safe` mode assembles with the other owners'.
+fn safe_owner_sign(
+    args: &Args,
+    input: &Input,
+    raw_input: &str,
+    owner: Address,
+    outfile: &Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (safe, chain_id) = args.safe(input)?;
+    let digest = input
+        .encode(SignatureType::ERC1271, input.signer)
+        .signature
+        .hash;
+    let safe_hash = safe::safe_message_hash(safe, chain_id, &digest);
+
+    let Some(signer) = args.signer()? else {
+        return Err(
+            "safe-owner-sign mode requires a signer subcommand or a --signer profile".into(),
+        );
+    };
+    let detached = sign_safe_hash(&safe_hash, &signer, owner)?;
+    args.signing_log()?.append(
+        safe_hash,
+        owner,
+        Some(input),
+        raw_input.as_bytes(),
+        signer.signer_type.name(),
+    )?;
+    write_detached(args, input, &detached, outfile, |report| {
+        report.safe_hash = Some(safe_hash);
+    })
+}
+
+fn write_detached(
+    args: &Args,
+    input: &Input,
+    detached: &DetachedSignature,
+    outfile: &Output,
+    add_to_report: impl FnOnce(&mut Report),
+) -> Result<(), Box<dyn std::error::Error>> {
+    outfile.write(&detached.to_json(), args.force)?;
+    if let Output::File(path) = outfile {
+        eprintln!("written to: {:?}", path);
+    }
+    if args.json() {
+        let mut report = Report::for_input(&args.mode, input).written_to(outfile);
+        report.signer = Some(detached.signer.to_checksum(None));
+        report.signature = Some(detached.signature.clone());
+        add_to_report(&mut report);
+        report.emit()?;
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = Args::parse();
+    let (config, sources) = Config::load(args.config.as_deref())?;
+    args.apply(&config)?;
+    let signed_output = SignedOutput {
+        dir: args.output_dir.clone(),
+        format: config
+            .encoding
+            .format
+            .as_deref()
+            .map(str::parse)
+            .transpose()?,
+    };
+
+    if args.mode == "config-show" {
+        return config_show(&args, &config, &sources);
+    }
+
+    if args.mode == "codegen" {
+        return codegen(&args);
+    }
+
+    if args.mode == "diff" {
+        return diff(&args);
+    }
+
+    if args.mode == "decode" {
+        return decode(&args);
+    }
+
+    if args.mode == "verify" {
+        return verify(&args);
+    }
+
+    if args.mode == "sign-request" {
+        return sign_request(&args);
+    }
+
+    if args.mode == "log-verify" {
+        return log_verify(&args);
+    }
+
+    if args.mode == "init" {
+        let input = init::run(
+            std::io::stdin().lock(),
+            std::io::stderr(),
+            config.auditor.as_ref(),
+        )?;
+        let outfile = args.outfile();
+        let format = output_format(&outfile, Format::Json);
+        return write_document(&args, &outfile, &format.serialize(&input)?, args.force);
+    }
+
+    if args.mode == "batch" {
+        let pattern = args.input.clone().ok_or("--input is required")?;
+        let files = batch::collect_inputs(&pattern)?;
+        signed_output.create_dir()?;
+        // Unsigned batches need no signing log
+        let signer = args
+            .signer()?
+            .map(|signer| args.signing_log().map(|log| (signer, log)))
+            .transpose()?;
+        let manifest = batch::run(
+            &files,
+            signer.as_ref().map(|(signer, log)| (signer, log)),
+            &signed_output,
+            &args.load_profiles()?,
+            args.force,
+        );
+        args.outfile()
+            .write(&(serde_json::to_string_pretty(&manifest)? + "\n"), true)?;
+        if manifest.failed() > 0 {
+            eprintln!("{} of {} files failed", manifest.failed(), files.len());
+            exit(1);
+        }
+        return Ok(());
+    }
+
+    let input_path = args.input.clone().ok_or("--input is required")?;
+    // Read the input file
+    let format = Format::resolve(args.format.as_deref(), &input_path)?;
+    let raw_input = files::read_to_string(&input_path)?;
+
+    let mut input: Input = format.parse_with(&raw_input, &args.load_profiles()?)?;
+    let normalized = report::normalize(&mut input)?;
+    let onchain_data = input.encode(SignatureType::SECP256K1, Address::default());
 
     match args.mode.as_str() {
+        "print" | "bytes" | "hash" if args.json() => {
+            Report::for_input(&args.mode, &input)
+                .normalized(normalized)
+                .emit()?;
+        }
         "print" => {
             println!("{:#?}", onchain_data);
             let data_bytes = Bytes::from(onchain_data.abi_encode());
@@ -107,125 +937,177 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             let data_bytes = Bytes::from(onchain_data.abi_encode());
             println!("{:?}", data_bytes);
         }
+        "render" => {
+            let rendered = match args.lang.as_deref().unwrap_or("markdown") {
+                "markdown" => render::markdown(&input),
+                "html" => render::html(&input),
+                lang => return Err(format!("Unsupported render language: {}", lang).into()),
+            };
+            write_output(&args, &rendered)?;
+        }
+        "fmt" => {
+            if format != Format::Json {
+                return Err(
+                    "fmt only normalizes JSON inputs, use convert for YAML and TOML".into(),
+                );
+            }
+            let canonical = input.to_canonical_json();
+            if args.check {
+                if raw_input != canonical {
+                    let warning = format!("{:?} is not canonical", input_path);
+                    if args.json() {
+                        let mut report = Report::new(&args.mode);
+                        report.warnings.push(warning.clone());
+                        report.emit()?;
+                    }
+                    eprintln!("{}, rerun fmt without --check", warning);
+                    exit(1);
+                }
+            } else {
+                // Normalize in place unless an explicit output is given
+                let outfile = Output::from(args.output.as_deref().unwrap_or(&input_path));
+                if raw_input != canonical || args.output.is_some() {
+                    outfile.write(&canonical, true)?;
+                }
+                if args.json() {
+                    Report::new(&args.mode).written_to(&outfile).emit()?;
+                }
+            }
+        }
+        "convert" => {
+            let outfile = args.outfile();
+            let target = match (&args.to, &outfile) {
+                (Some(to), _) => to.parse()?,
+                (None, Output::File(path)) => {
+                    Format::from_path(path).ok_or("--to is required for this --output")?
+                }
+                (None, Output::Stdout) => return Err("--to is required without --output".into()),
+            };
+            write_document(&args, &outfile, &target.serialize(&input)?, args.force)?;
+        }
         "hash" => {
             let digest = onchain_data.signature.hash.to_vec();
             println!("{:?}", Bytes::from(digest.clone()));
         }
         "cast" => {
-
             let digest = onchain_data.signature.hash.to_vec();
             let digest_bytes = Bytes::from(digest.clone());
-            println!("signing hash:  {:?}", digest_bytes);
-
-
-            let foo = to_hex_string(&digest);
-
-            let output;
-
-            match args.signer_type{
-                Some(SignerType::PrivateKey{private_key}) => {
-                     output = Command::new("cast")
-                        .arg("wallet")
-                        .arg("sign")
-                        .arg(foo)
-                        .arg("--private-key")
-                        .arg(private_key)
-                        .output()
-                        .expect("Failed to execute command");
-                },
-                Some(SignerType::Interactive) => {
-                     output = Command::new("cast")
-                        .arg("wallet")
-                        .arg("sign")
-                        .arg(foo)
-                        .arg("--interactive")
-                        .output()
-                        .expect("Failed to execute command");
-                },
+            // Status messages go to stderr so the signed JSON can be piped from stdout
+            eprintln!("signing hash:  {:?}", digest_bytes);
 
-                Some(SignerType::Ledger) => {
-                     output = Command::new("cast")
-                        .arg("wallet")
-                        .arg("sign")
-                        .arg(foo)
-                        .arg("--ledger")
-                        .output()
-                        .expect("Failed to execute command");
-                },
+            // Resolved before signing, so that a refused output does not waste a signature
+            let outfile = signed_outfile(&args, &input_path, &signed_output, |signed_output| {
+                if args.detached {
+                    let address = args.sign_as.unwrap_or(input.signer);
+                    let cosigner = (address != input.signer).then_some(address);
+                    signed_output.detached_path(&input_path, cosigner)
+                } else {
+                    signed_output.path(&input_path)
+                }
+            })?;
+            outfile.check_writable(args.force)?;
 
-                Some(SignerType::Trezor) => {
-                     output = Command::new("cast")
-                        .arg("wallet")
-                        .arg("sign")
-                        .arg(foo)
-                        .arg("--trezor")
-                        .output()
-                        .expect("Failed to execute command");
-                },
-                _ => {
-                    println!("Invalid mode");
-                    exit(-1);
+            let Some(signer) = args.signer()? else {
+                return Err("cast mode requires a signer subcommand or a --signer profile".into());
+            };
+            if args.detached {
+                return cast_detached(&args, &input, &raw_input, &signer, &outfile);
+            }
+            let output = sign_input(&input, &signer)?;
+            args.signing_log()?.append(
+                onchain_data.signature.hash,
+                input.signer,
+                Some(&input),
+                raw_input.as_bytes(),
+                signer.signer_type.name(),
+            )?;
+            write_signed(&args, &output, &outfile, format)?;
+        }
+        "prepare" => {
+            let request = SigningRequest::new(&input);
+            let outfile = signed_outfile(&args, &input_path, &signed_output, |signed_output| {
+                signed_output.request_path(&input_path)
+            })?;
+            // Written first, so that the request is on disk even if the QR code cannot be shown
+            outfile.write(&request.to_json(), args.force)?;
+            eprint!("{}", request.summary);
+            eprintln!("signing hash:  {}", request.digest);
+            eprintln!("payload:       {}", request.payload);
+            match airgap::qr(&request.payload) {
+                Ok(code) => eprint!("{}", code),
+                Err(e) => eprintln!(
+                    "warning: the payload does not fit a QR code ({}), carry the request file",
+                    e
+                ),
+            }
+            if let Output::File(path) = &outfile {
+                eprintln!("written to: {:?}", path);
+            }
+            if args.json() {
+                Report::for_input(&args.mode, &input)
+                    .written_to(&outfile)
+                    .emit()?;
+            }
+        }
+        "finalize" => {
+            let [path] = args.signatures.as_slice() else {
+                return Err("finalize requires exactly one --signature".into());
+            };
+            let (detached, signature) = airgap::read_response(&files::read_to_string(path)?)?;
+            if let Some(detached) = detached {
+                let digest = onchain_data.signature.hash;
+                if detached.digest != digest {
+                    return Err(format!(
+                        "{:?} signs digest {}, but the input's digest is {}",
+                        path, detached.digest, digest
+                    )
+                    .into());
                 }
             }
-
-
-            let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
-            let signature = stdout.trim().to_string(); // R
-
-            let mut output = input;
-            output.signature = Some(JsonSignature {
-                hash: to_hex_string(&digest),
-                signature: Bytes::from(from_hex_string(&signature).unwrap()),
-            });
-
-            // Create the outfile name and keep it in a variable
-            let outfile_name_string = to_outfile(&input_path);
-            let outfile_name = Path::new(&outfile_name_string);
-            println!("written to: {:?}", outfile_name);
-
-            // Create the file
-            let mut file = File::create(outfile_name)?;
-
-            // Serialize the struct to JSON
-            let json_string = serde_json::to_string(&output)
-                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-
-            // Write the JSON to the file
-            file.write_all(json_string.as_bytes())?;
-        }
-        "sign" => {
-            // let private_key = args.private_key;
-            //
-            // let input: Input = serde_json::from_str(&json_input)?;
-            // let signer = PrivateKeySigner::from_str(&private_key.unwrap())?;
-            // onchain_data = input.encode(SignatureType::SECP256K1, signer.address());
-            //
-            // // let data_bytes = Bytes::from(onchain_data.abi_encode());
-            //
-            // let digest = onchain_data.signature.hash.to_vec();
-            // // println!("{:?}", Bytes::from(digest.clone()));
-            //
-            // // let signer = PrivateKeySigner::from_str(&private_key.unwrap())?;
-            // let sig = signer.sign_message_sync(&digest)?;
-            // // println!("{:?}", sig);
-            //
-            // let packed_sig: PackedSig = PackedSig {
-            //     r: sig.r().into(),
-            //     s: sig.s().into(),
-            //     v: sig.v().to_u64() as u8,
-            // };
-            //
-            // let packed = PackedSig::abi_encode_packed(&packed_sig);
-            // // println!("{:?}", packed);
-            // let packed_bytes = Bytes::from(packed);
-            // onchain_data.signature.signatureData = packed_bytes;
-            // // onchain_data.signature.signatureData = signer.sign_message_sync(&digest)?;
-            // let data_bytes = Bytes::from(onchain_data.abi_encode());
-            // println!("{:?}", data_bytes);
-        }
-        _ => {
-            println!("Invalid mode");
+            let output = attach_signature(&input, &signature)?;
+            let outfile = signed_outfile(&args, &input_path, &signed_output, |signed_output| {
+                signed_output.path(&input_path)
+            })?;
+            write_signed(&args, &output, &outfile, format)?;
+        }
+        "safe-hash" => {
+            let (safe, chain_id) = args.safe(&input)?;
+            let digest = onchain_data.signature.hash;
+            let safe_hash = safe::safe_message_hash(safe, chain_id, &digest);
+            if args.json() {
+                let mut report = Report::for_input(&args.mode, &input);
+                report.signer = Some(safe.to_checksum(None));
+                report.safe_hash = Some(safe_hash);
+                report.emit()?;
+            } else {
+                println!("{}", safe_hash);
+            }
+        }
+        "safe-owner-sign" => {
+            let owner = args
+                .owner
+                .ok_or("safe-owner-sign mode requires the signing --owner")?;
+            let outfile = signed_outfile(&args, &input_path, &signed_output, |signed_output| {
+                signed_output.owner_signature_path(&input_path, owner)
+            })?;
+            outfile.check_writable(args.force)?;
+            safe_owner_sign(&args, &input, &raw_input, owner, &outfile)?;
+        }
+        "safe" => {
+            let output = safe_sign(&args, &input)?;
+            let outfile = signed_outfile(&args, &input_path, &signed_output, |signed_output| {
+                signed_output.path(&input_path)
+            })?;
+            write_signed(&args, &output, &outfile, format)?;
+        }
+        "passkey" => {
+            let output = attach_passkey(&args, &input)?;
+            let outfile = signed_outfile(&args, &input_path, &signed_output, |signed_output| {
+                signed_output.path(&input_path)
+            })?;
+            write_signed(&args, &output, &outfile, format)?;
         }
+        mode => return Err(format!("Invalid mode: {}", mode).into()),
     }
 
     Ok(())
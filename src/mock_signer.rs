@@ -0,0 +1,155 @@
+//! Minimal signing service for tests and local experiments. It speaks the JSON-RPC (`eth_sign`,
+//! `personal_sign`, `eth_accounts`) and Web3Signer (`/api/v1/eth1/sign/<address>`, `/upcheck`)
+//! APIs over plain HTTP, one connection per request.
+
+use alloy_primitives::{Address, Bytes};
+use alloy_signer::SignerSync;
+use alloy_signer_local::PrivateKeySigner;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::thread;
+
+pub struct MockSigner {
+    pub addr: SocketAddr,
+}
+
+impl MockSigner {
+    /// Serves signatures by `key` on `addr`, e.g. `127.0.0.1:0` for a free port. With a `token`,
+    /// requests must carry `Authorization: Bearer <token>`.
+    pub fn bind(
+        addr: impl ToSocketAddrs,
+        key: PrivateKeySigner,
+        token: Option<String>,
+    ) -> io::Result<MockSigner> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let _ = handle(stream, &key, token.as_deref());
+            }
+        });
+        Ok(MockSigner { addr })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let Some((name, value)) = line.trim_end().split_once(':') else {
+            break;
+        };
+        headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+    }
+
+    let length = headers
+        .get("content-length")
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Request {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+fn respond(mut stream: &TcpStream, status: u16, content_type: &str, body: &str) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        _ => "Bad Request",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+/// Signs hex `data` as a personal message if `address` is the served key.
+fn sign(key: &PrivateKeySigner, data: &Value, address: &Value) -> Result<String, String> {
+    let address: Address = address
+        .as_str()
+        .unwrap_or_default()
+        .parse()
+        .map_err(|e| format!("invalid address: {}", e))?;
+    if address != key.address() {
+        return Err(format!("unknown account {}", address));
+    }
+    let data: Bytes = data
+        .as_str()
+        .unwrap_or_default()
+        .parse()
+        .map_err(|e| format!("invalid data: {}", e))?;
+    let signature = key.sign_message_sync(&data).map_err(|e| e.to_string())?;
+    Ok(Bytes::from(signature.as_bytes().to_vec()).to_string())
+}
+
+fn handle(stream: TcpStream, key: &PrivateKeySigner, token: Option<&str>) -> io::Result<()> {
+    let request = read_request(&stream)?;
+    if let Some(token) = token {
+        if request.headers.get("authorization") != Some(&format!("Bearer {}", token)) {
+            return respond(&stream, 401, "text/plain", "missing or invalid token");
+        }
+    }
+    let body: Value = serde_json::from_slice(&request.body).unwrap_or_default();
+
+    if let Some(address) = request.path.strip_prefix("/api/v1/eth1/sign/") {
+        return match sign(key, &body["data"], &address.into()) {
+            Ok(signature) => respond(&stream, 200, "text/plain", &signature),
+            Err(e) => respond(&stream, 404, "text/plain", &e),
+        };
+    }
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/upcheck") => respond(&stream, 200, "text/plain", "OK"),
+        ("POST", "/") => {
+            let params = &body["params"];
+            let result = match body["method"].as_str() {
+                Some("eth_accounts") => Ok(json!([key.address()])),
+                Some("eth_sign") => sign(key, &params[1], &params[0]).map(Value::from),
+                Some("personal_sign") => sign(key, &params[0], &params[1]).map(Value::from),
+                method => Err(format!("method not found: {:?}", method)),
+            };
+            let response = match result {
+                Ok(result) => json!({"jsonrpc": "2.0", "id": body["id"], "result": result}),
+                Err(message) => json!({
+                    "jsonrpc": "2.0",
+                    "id": body["id"],
+                    "error": {"code": -32000, "message": message}
+                }),
+            };
+            respond(&stream, 200, "application/json", &response.to_string())
+        }
+        _ => respond(&stream, 404, "text/plain", "not found"),
+    }
+}
@@ -0,0 +1,183 @@
+//! Signing with a secp256k1 key kept in an HSM, through the vendor's PKCS#11 module.
+
+use alloy_primitives::eip191_hash_message;
+use clap::Args;
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::slot::Slot;
+use cryptoki::types::AuthPin;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::error::Error;
+use std::path::PathBuf;
+
+use attestation_rs::signature::{normalize_ecdsa, public_key_address};
+
+use crate::plugin::{SignRequest, SignResponse};
+
+#[derive(Args, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Pkcs11Options {
+    /// PKCS#11 module of the HSM, e.g. `/usr/lib/softhsm/libsofthsm2.so`
+    #[arg(long)]
+    pub module: PathBuf,
+    /// ID of the slot holding the key
+    #[arg(long)]
+    pub slot: u64,
+    /// `CKA_LABEL` of the key pair
+    #[arg(long)]
+    pub key_label: String,
+    /// Environment variable holding the user PIN
+    #[arg(long, default_value = "PKCS11_PIN")]
+    #[serde(default = "default_pin_env")]
+    pub pin_env: String,
+}
+
+fn default_pin_env() -> String {
+    "PKCS11_PIN".to_string()
+}
+
+fn find_key(
+    session: &Session,
+    class: ObjectClass,
+    label: &str,
+) -> Result<ObjectHandle, Box<dyn Error>> {
+    let template = [
+        Attribute::Class(class),
+        Attribute::KeyType(KeyType::EC),
+        Attribute::Label(label.as_bytes().to_vec()),
+    ];
+    match session.find_objects(&template)?[..] {
+        [key] => Ok(key),
+        [] => Err(format!("No EC {} labelled {:?}", class, label).into()),
+        _ => Err(format!("Several EC {} objects are labelled {:?}", class, label).into()),
+    }
+}
+
+/// Signs `request.digest` as an EIP-191 personal message with the key labelled
+/// `options.key_label`. The address is derived from the key's public point.
+pub fn sign(
+    options: &Pkcs11Options,
+    request: &SignRequest,
+) -> Result<SignResponse, Box<dyn Error>> {
+    let pin = env::var(&options.pin_env)
+        .map_err(|_| format!("Set {} to the user PIN of the HSM slot", options.pin_env))?;
+
+    let pkcs11 = Pkcs11::new(&options.module)
+        .map_err(|e| format!("Cannot load PKCS#11 module {:?}: {}", options.module, e))?;
+    pkcs11.initialize(CInitializeArgs::OsThreads)?;
+    let session = pkcs11.open_ro_session(Slot::try_from(options.slot)?)?;
+    session.login(UserType::User, Some(&AuthPin::new(pin)))?;
+
+    let public_key = find_key(&session, ObjectClass::PUBLIC_KEY, &options.key_label)?;
+    let point = session
+        .get_attributes(public_key, &[AttributeType::EcPoint])?
+        .into_iter()
+        .find_map(|attribute| match attribute {
+            Attribute::EcPoint(point) => Some(point),
+            _ => None,
+        })
+        .ok_or("Public key has no CKA_EC_POINT")?;
+    let address = public_key_address(&point)?;
+
+    // CKM_ECDSA signs the hash as given, so apply the EIP-191 prefix here
+    let prehash = eip191_hash_message(request.digest);
+    let private_key = find_key(&session, ObjectClass::PRIVATE_KEY, &options.key_label)?;
+    let signature = session.sign(&Mechanism::Ecdsa, private_key, prehash.as_slice())?;
+
+    Ok(SignResponse {
+        signature: normalize_ecdsa(&signature, &prehash, address)?,
+        address,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::PROTOCOL_VERSION;
+    use crate::fixtures::scratch_dir;
+    use alloy_primitives::B256;
+    use attestation_rs::signature::recover_signer;
+
+    /// DER encoding of the secp256k1 curve OID, 1.3.132.0.10
+    const SECP256K1: [u8; 7] = [0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x0a];
+
+    /// Creates a token with a fresh secp256k1 key in a temporary SoftHSM store, signs with it and
+    /// checks the signature. Run with
+    /// `SOFTHSM2_MODULE=/usr/lib/softhsm/libsofthsm2.so cargo test --features pkcs11 -- --ignored`.
+    #[test]
+    #[ignore = "needs SoftHSM, see the doc comment"]
+    fn test_softhsm_sign() {
+        let module = PathBuf::from(env::var("SOFTHSM2_MODULE").expect("SOFTHSM2_MODULE"));
+        let dir = scratch_dir("softhsm");
+        std::fs::create_dir_all(dir.join("tokens")).unwrap();
+        std::fs::write(
+            dir.join("softhsm2.conf"),
+            format!("directories.tokendir = {}\n", dir.join("tokens").display()),
+        )
+        .unwrap();
+        env::set_var("SOFTHSM2_CONF", dir.join("softhsm2.conf"));
+        env::set_var("ATTESTATION_TEST_PIN", "1234");
+
+        let slot = {
+            let pkcs11 = Pkcs11::new(&module).unwrap();
+            pkcs11.initialize(CInitializeArgs::OsThreads).unwrap();
+            let slot = pkcs11.get_all_slots().unwrap()[0];
+            let so_pin = AuthPin::new("5678".into());
+            pkcs11.init_token(slot, &so_pin, "attestation").unwrap();
+            // SoftHSM moves the initialized token to a new slot
+            let slot = pkcs11.get_slots_with_initialized_token().unwrap()[0];
+
+            let session = pkcs11.open_rw_session(slot).unwrap();
+            session.login(UserType::So, Some(&so_pin)).unwrap();
+            session.init_pin(&AuthPin::new("1234".into())).unwrap();
+            session.logout().unwrap();
+            session
+                .login(UserType::User, Some(&AuthPin::new("1234".into())))
+                .unwrap();
+            let label = Attribute::Label(b"auditor".to_vec());
+            session
+                .generate_key_pair(
+                    &Mechanism::EccKeyPairGen,
+                    &[
+                        Attribute::Token(true),
+                        Attribute::Verify(true),
+                        Attribute::EcParams(SECP256K1.to_vec()),
+                        label.clone(),
+                    ],
+                    &[
+                        Attribute::Token(true),
+                        Attribute::Private(true),
+                        Attribute::Sign(true),
+                        label,
+                    ],
+                )
+                .unwrap();
+            slot.id()
+        };
+
+        let options = Pkcs11Options {
+            module,
+            slot,
+            key_label: "auditor".to_string(),
+            pin_env: "ATTESTATION_TEST_PIN".to_string(),
+        };
+        let request = SignRequest {
+            version: PROTOCOL_VERSION,
+            digest: B256::repeat_byte(0x42),
+            sig_type: "SECP256K1".to_string(),
+            address: Default::default(),
+        };
+        let response = sign(&options, &request);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let response = response.unwrap();
+        assert_eq!(response.signature.len(), 65);
+        assert_eq!(
+            recover_signer(&request.digest, &response.signature).unwrap(),
+            response.address
+        );
+    }
+}
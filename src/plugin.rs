@@ -0,0 +1,381 @@
+//! Signer plugins: executables that sign digests on behalf of the CLI.
+//!
+//! A plugin is spawned once per digest and receives a single JSON request on stdin:
+//!
+//! ```json
+//! {"version": 1, "digest": "0x…", "sigType": "SECP256K1", "address": "0x…"}
+//! ```
+//!
+//! It signs `digest` as an EIP-191 personal message with the key of `address` and prints a JSON
+//! response on stdout, exiting with status 0:
+//!
+//! ```json
+//! {"signature": "0x…", "address": "0x…"}
+//! ```
+//!
+//! On failure it exits with a non-zero status, optionally printing `{"error": "…"}` on stdout.
+//! Anything written to stderr is shown to the user, so plugins can prompt there.
+
+use alloy_primitives::{Address, Bytes, B256};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use attestation_rs::signature::recover_signer;
+
+use crate::signer::{from_hex_string, to_hex_string};
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Default for `--signer-timeout`, long enough to confirm on a hardware wallet.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignRequest {
+    pub version: u32,
+    pub digest: B256,
+    pub sig_type: String,
+    /// Address the signature is expected from
+    pub address: Address,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct SignResponse {
+    pub signature: Bytes,
+    pub address: Address,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+#[derive(Debug)]
+pub enum PluginError {
+    NotFound(PathBuf),
+    Io(io::Error),
+    TimedOut(Duration),
+    Failed { status: ExitStatus, stderr: String },
+    InvalidResponse(String),
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PluginError::NotFound(path) if path.ends_with("cast") => write!(
+                f,
+                "cast not found at {:?}, install Foundry or set cast-path",
+                path
+            ),
+            PluginError::NotFound(path) => write!(f, "Signer plugin {:?} not found", path),
+            PluginError::Io(e) => write!(f, "Failed to run signer: {}", e),
+            PluginError::TimedOut(timeout) => {
+                write!(f, "Signer did not finish within {}s", timeout.as_secs_f32())
+            }
+            PluginError::Failed { status, stderr } => {
+                write!(f, "Signer failed ({}): {}", status, stderr.trim())
+            }
+            PluginError::InvalidResponse(reason) => {
+                write!(f, "Signer returned an invalid response: {}", reason)
+            }
+        }
+    }
+}
+
+impl Error for PluginError {}
+
+pub trait SignerPlugin {
+    fn sign(&self, request: &SignRequest) -> Result<SignResponse, PluginError>;
+}
+
+/// Collects a child's output on a separate thread, optionally echoing it to our stderr so that
+/// prompts stay visible.
+fn collect<R: Read + Send + 'static>(pipe: Option<R>, echo: bool) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut collected = Vec::new();
+        let Some(mut pipe) = pipe else {
+            return collected;
+        };
+        let mut chunk = [0u8; 1024];
+        while let Ok(n @ 1..) = pipe.read(&mut chunk) {
+            if echo {
+                let _ = io::stderr().write_all(&chunk[..n]);
+            }
+            collected.extend_from_slice(&chunk[..n]);
+        }
+        collected
+    })
+}
+
+fn wait(child: &mut Child, timeout: Option<Duration>) -> Result<ExitStatus, PluginError> {
+    let start = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait().map_err(PluginError::Io)? {
+            return Ok(status);
+        }
+        if let Some(timeout) = timeout.filter(|timeout| start.elapsed() >= *timeout) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(PluginError::TimedOut(timeout));
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+/// Runs `command` to completion, writing `stdin` to it if given, and returns its stdout.
+fn run(
+    mut command: Command,
+    stdin: Option<Vec<u8>>,
+    timeout: Option<Duration>,
+) -> Result<Vec<u8>, PluginError> {
+    let program = PathBuf::from(command.get_program());
+    let mut child = command
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::inherit()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => PluginError::NotFound(program),
+            _ => PluginError::Io(e),
+        })?;
+
+    if let (Some(data), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        // Closing the pipe after writing signals the end of the request
+        thread::spawn(move || pipe.write_all(&data));
+    }
+    let stdout = collect(child.stdout.take(), false);
+    // Shows hardware wallet prompts as they come, but not in the output of the tests
+    let stderr = collect(child.stderr.take(), !cfg!(test));
+    let status = wait(&mut child, timeout)?;
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    if !status.success() {
+        let stderr = match serde_json::from_slice::<ErrorResponse>(&stdout) {
+            Ok(response) => response.error,
+            Err(_) => String::from_utf8_lossy(&stderr).into_owned(),
+        };
+        return Err(PluginError::Failed { status, stderr });
+    }
+    Ok(stdout)
+}
+
+/// An executable speaking the JSON protocol described in the module documentation.
+#[derive(Debug, Clone)]
+pub struct ExternalPlugin {
+    pub command: PathBuf,
+    pub args: Vec<String>,
+    pub timeout: Option<Duration>,
+}
+
+impl SignerPlugin for ExternalPlugin {
+    fn sign(&self, request: &SignRequest) -> Result<SignResponse, PluginError> {
+        let mut command = Command::new(&self.command);
+        command.args(&self.args);
+        let request = serde_json::to_vec(request).expect("sign request serializes to JSON");
+
+        let stdout = run(command, Some(request), self.timeout)?;
+        serde_json::from_slice(&stdout).map_err(|e| {
+            PluginError::InvalidResponse(format!("{}: {}", e, String::from_utf8_lossy(&stdout)))
+        })
+    }
+}
+
+/// How external signers are invoked.
+#[derive(Debug, Clone)]
+pub struct SignerOptions {
+    /// `cast` binary for the signers that delegate to it
+    pub cast_path: PathBuf,
+    /// Give up on `cast`, a signer plugin or a remote signer if it has not answered after this
+    /// long
+    pub timeout: Option<Duration>,
+}
+
+impl Default for SignerOptions {
+    fn default() -> Self {
+        SignerOptions {
+            cast_path: PathBuf::from("cast"),
+            timeout: Some(DEFAULT_TIMEOUT),
+        }
+    }
+}
+
+/// Built-in plugin signing with `cast wallet sign`.
+pub struct CastPlugin<'a> {
+    /// `cast wallet sign` flag selecting the wallet, e.g. `--ledger`
+    pub wallet: &'a str,
+    pub options: &'a SignerOptions,
+}
+
+impl SignerPlugin for CastPlugin<'_> {
+    fn sign(&self, request: &SignRequest) -> Result<SignResponse, PluginError> {
+        let mut command = Command::new(&self.options.cast_path);
+        command
+            .arg("wallet")
+            .arg("sign")
+            .arg(to_hex_string(request.digest.as_slice()))
+            .arg(self.wallet);
+
+        let stdout = run(command, None, self.options.timeout)?;
+        let stdout = String::from_utf8_lossy(&stdout);
+        let signature = stdout.trim();
+        let bytes = from_hex_string(signature)
+            .map_err(|e| PluginError::InvalidResponse(format!("{:?}: {}", signature, e)))?;
+        if bytes.len() != 65 {
+            return Err(PluginError::InvalidResponse(format!(
+                "expected 65 bytes, got {}",
+                bytes.len()
+            )));
+        }
+
+        // cast does not report the account it used, so recover it
+        let address = recover_signer(&request.digest, &bytes)
+            .map_err(|e| PluginError::InvalidResponse(e.to_string()))?;
+        Ok(SignResponse {
+            signature: Bytes::from(bytes),
+            address,
+        })
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+
+    use crate::fixtures::scratch_dir;
+
+    /// Writes an executable shell script to a temporary file.
+    fn script(name: &str, body: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = scratch_dir(name).join("plugin");
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn request(address: Address) -> SignRequest {
+        SignRequest {
+            version: PROTOCOL_VERSION,
+            digest: B256::repeat_byte(0x42),
+            sig_type: "SECP256K1".to_string(),
+            address,
+        }
+    }
+
+    fn sign_with(plugin: &dyn SignerPlugin, address: Address) -> Result<SignResponse, PluginError> {
+        plugin.sign(&request(address))
+    }
+
+    #[test]
+    fn test_external_plugin_round_trip() {
+        let key = PrivateKeySigner::random();
+        let signature = key
+            .sign_message_sync(B256::repeat_byte(0x42).as_slice())
+            .unwrap();
+        let expected = SignResponse {
+            signature: Bytes::from(signature.as_bytes().to_vec()),
+            address: key.address(),
+        };
+        let command = script(
+            "plugin-ok",
+            &format!(
+                "echo '{{\"signature\": \"{}\", \"address\": \"{}\"}}'",
+                expected.signature, expected.address
+            ),
+        );
+        let plugin = ExternalPlugin {
+            command: command.clone(),
+            args: vec![],
+            timeout: Some(Duration::from_secs(5)),
+        };
+
+        let response = sign_with(&plugin, key.address());
+        std::fs::remove_file(command).unwrap();
+
+        assert_eq!(response.unwrap(), expected);
+    }
+
+    #[test]
+    fn test_plugin_errors() {
+        let sign = |name, body| {
+            let command = script(name, body);
+            let plugin = ExternalPlugin {
+                command: command.clone(),
+                args: vec![],
+                timeout: Some(Duration::from_millis(500)),
+            };
+            let result = sign_with(&plugin, Address::ZERO);
+            std::fs::remove_file(command).unwrap();
+            result.unwrap_err()
+        };
+
+        assert!(matches!(
+            sign("plugin-denied", "echo '{\"error\": \"denied by policy\"}'; exit 3"),
+            PluginError::Failed { stderr, .. } if stderr == "denied by policy"
+        ));
+        assert!(matches!(
+            sign("plugin-garbage", "echo nope"),
+            PluginError::InvalidResponse(_)
+        ));
+        assert!(matches!(
+            sign("plugin-slow", "sleep 5"),
+            PluginError::TimedOut(_)
+        ));
+    }
+
+    #[test]
+    fn test_cast_errors() {
+        let sign = |name, body| {
+            let options = SignerOptions {
+                cast_path: script(name, body),
+                timeout: Some(Duration::from_millis(500)),
+            };
+            let plugin = CastPlugin {
+                wallet: "--ledger",
+                options: &options,
+            };
+            let result = sign_with(&plugin, Address::ZERO);
+            std::fs::remove_file(&options.cast_path).unwrap();
+            result.unwrap_err()
+        };
+
+        assert!(matches!(
+            sign("cast-failed", "echo 'Error: device not found' >&2; exit 1"),
+            PluginError::Failed { stderr, .. } if stderr.contains("device not found")
+        ));
+        assert!(matches!(
+            sign("cast-short", "echo 0x1234"),
+            PluginError::InvalidResponse(_)
+        ));
+        assert!(matches!(
+            sign("cast-empty", "true"),
+            PluginError::InvalidResponse(_)
+        ));
+
+        let missing = SignerOptions {
+            cast_path: PathBuf::from("/nonexistent/cast"),
+            timeout: None,
+        };
+        let plugin = CastPlugin {
+            wallet: "--ledger",
+            options: &missing,
+        };
+        assert!(matches!(
+            sign_with(&plugin, Address::ZERO),
+            Err(PluginError::NotFound(_))
+        ));
+    }
+}
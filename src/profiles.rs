@@ -0,0 +1,121 @@
+//! Auditor profiles, so that attestations can reference an auditor by ID instead of repeating the
+//! `auditor` block.
+
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use crate::types::{Input, JsonAuditor};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditorProfile {
+    #[serde(flatten)]
+    pub auditor: JsonAuditor,
+    /// Addresses allowed to sign attestations for this auditor
+    pub signers: Vec<Address>,
+}
+
+/// Profiles by ID.
+pub type AuditorProfiles = BTreeMap<String, AuditorProfile>;
+
+/// An `auditor` spelled out inline, or the ID of one of the profiles.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum AuditorRef {
+    Profile(String),
+    Inline(JsonAuditor),
+}
+
+/// An input as written, before a referenced auditor profile is resolved.
+pub type ProfiledInput = Input<AuditorRef>;
+
+impl ProfiledInput {
+    /// Inlines a referenced profile, so the digest is the same as for an input that spells the
+    /// auditor out. `signer` must then be one of the profile's signers.
+    pub fn resolve(self, profiles: &AuditorProfiles) -> Result<Input, Box<dyn Error>> {
+        let auditor = match self.auditor {
+            AuditorRef::Inline(auditor) => auditor,
+            AuditorRef::Profile(id) => {
+                let profile = profiles
+                    .get(&id)
+                    .ok_or_else(|| format!("Unknown auditor profile: {}", id))?;
+                if !profile.signers.contains(&self.signer) {
+                    return Err(format!(
+                        "Signer {} is not registered for auditor profile {}",
+                        self.signer.to_checksum(None),
+                        id
+                    )
+                    .into());
+                }
+                profile.auditor.clone()
+            }
+        };
+        Ok(Input {
+            title: self.title,
+            auditor,
+            report_url: self.report_url,
+            signer: self.signer,
+            module_attributes: self.module_attributes,
+            signature: self.signature,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::{SignAttestation, SignatureType};
+    use crate::types::tests::EXAMPLE_JSON;
+    use serde_json::Value;
+
+    fn example() -> Value {
+        serde_json::from_str(EXAMPLE_JSON).unwrap()
+    }
+
+    fn resolve_input(value: Value, profiles: &AuditorProfiles) -> Result<Input, Box<dyn Error>> {
+        serde_json::from_value::<ProfiledInput>(value)?.resolve(profiles)
+    }
+
+    fn profiles(signer: Address) -> AuditorProfiles {
+        let inline: Input = serde_json::from_value(example()).unwrap();
+        AuditorProfiles::from([(
+            "ackee".to_string(),
+            AuditorProfile {
+                auditor: inline.auditor,
+                signers: vec![signer],
+            },
+        )])
+    }
+
+    #[test]
+    fn test_profile_reference_has_inline_digest() {
+        let inline: Input = serde_json::from_value(example()).unwrap();
+        let mut referencing = example();
+        referencing["auditor"] = "ackee".into();
+
+        let resolved = resolve_input(referencing, &profiles(inline.signer)).unwrap();
+
+        assert_eq!(resolved, inline);
+        assert_eq!(
+            resolved
+                .encode(SignatureType::SECP256K1, resolved.signer)
+                .signature
+                .hash,
+            inline
+                .encode(SignatureType::SECP256K1, inline.signer)
+                .signature
+                .hash
+        );
+    }
+
+    #[test]
+    fn test_signer_must_be_registered() {
+        let mut referencing = example();
+        referencing["auditor"] = "ackee".into();
+
+        assert!(resolve_input(referencing.clone(), &profiles(Address::ZERO)).is_err());
+        assert!(resolve_input(referencing, &AuditorProfiles::new()).is_err());
+        assert!(resolve_input(example(), &AuditorProfiles::new()).is_ok());
+    }
+}
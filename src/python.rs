@@ -0,0 +1,88 @@
+//! PyO3 extension module. Attestations are passed as dicts (or JSON strings) and returned as dicts;
+//! encoded data and digests are returned as `bytes`.
+
+// `#[pyfunction]` expands to an `Into<PyErr>` conversion of `PyResult` errors
+#![allow(clippy::useless_conversion)]
+
+use alloy_sol_types::SolValue;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyString};
+
+use crate::abi::{decode_summary, HashAuditSummary, SignAttestation, SignatureType};
+use crate::signature::VerifyAttestation;
+use crate::types::Input;
+
+fn to_py_error<E: std::fmt::Display>(error: E) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
+fn to_py_object<'py, T: serde::Serialize>(
+    py: Python<'py>,
+    value: &T,
+) -> PyResult<Bound<'py, PyAny>> {
+    let json = serde_json::to_string(value).map_err(to_py_error)?;
+    py.import_bound("json")?.call_method1("loads", (json,))
+}
+
+fn from_py_input(input: &Bound<'_, PyAny>) -> PyResult<Input> {
+    let json: String = if input.is_instance_of::<PyString>() {
+        input.extract()?
+    } else {
+        input
+            .py()
+            .import_bound("json")?
+            .call_method1("dumps", (input,))?
+            .extract()?
+    };
+    serde_json::from_str(&json).map_err(to_py_error)
+}
+
+/// Validates an attestation and returns it as a dict.
+#[pyfunction]
+fn parse_input<'py>(input: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    to_py_object(input.py(), &from_py_input(input)?)
+}
+
+/// ABI-encodes the `AuditSummary` of an attestation.
+#[pyfunction]
+fn encode<'py>(input: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyBytes>> {
+    let attestation = from_py_input(input)?;
+    let summary = attestation.encode(SignatureType::SECP256K1, attestation.signer);
+    Ok(PyBytes::new_bound(input.py(), &summary.abi_encode()))
+}
+
+/// Returns the 32 byte digest that has to be signed for an attestation.
+#[pyfunction]
+fn digest<'py>(input: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyBytes>> {
+    let attestation = from_py_input(input)?;
+    let summary = attestation.encode(SignatureType::SECP256K1, attestation.signer);
+    Ok(PyBytes::new_bound(input.py(), summary.digest().as_slice()))
+}
+
+/// Decodes ABI-encoded `AuditSummary` bytes into an attestation dict.
+#[pyfunction]
+fn decode<'py>(py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyAny>> {
+    let summary = decode_summary(data).map_err(to_py_error)?;
+    let attestation = Input::decode(&summary).map_err(to_py_error)?;
+    to_py_object(py, &attestation)
+}
+
+/// Verifies the signature of a signed attestation.
+#[pyfunction]
+fn verify<'py>(input: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let attestation = from_py_input(input)?;
+    let summary = attestation.encode(SignatureType::SECP256K1, attestation.signer);
+    let verification = summary.verify().map_err(to_py_error)?;
+    to_py_object(input.py(), &verification)
+}
+
+#[pymodule]
+fn attestation_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(parse_input, m)?)?;
+    m.add_function(wrap_pyfunction!(encode, m)?)?;
+    m.add_function(wrap_pyfunction!(digest, m)?)?;
+    m.add_function(wrap_pyfunction!(decode, m)?)?;
+    m.add_function(wrap_pyfunction!(verify, m)?)?;
+    Ok(())
+}
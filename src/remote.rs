@@ -0,0 +1,346 @@
+//! Signing through a remote service: a JSON-RPC node with unlocked accounts (`eth_sign` or
+//! `personal_sign`), or an HTTP API compatible with Web3Signer's `/api/v1/eth1/sign`.
+
+use alloy_primitives::Bytes;
+use clap::{Args, ValueEnum};
+use rustls::{ClientConfig, RootCertStore};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use attestation_rs::signature::recover_signer;
+
+use crate::plugin::{SignRequest, SignResponse};
+use crate::signer::to_hex_string;
+
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RemoteApi {
+    /// JSON-RPC `eth_sign(address, data)`
+    #[default]
+    EthSign,
+    /// JSON-RPC `personal_sign(data, address)`
+    PersonalSign,
+    /// Web3Signer `POST /api/v1/eth1/sign/<address>`
+    Web3signer,
+}
+
+#[derive(Args, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RemoteOptions {
+    /// JSON-RPC endpoint, or base URL of the Web3Signer API
+    #[arg(long)]
+    pub url: String,
+    #[arg(long, value_enum, default_value_t)]
+    #[serde(default)]
+    pub api: RemoteApi,
+    /// HTTP header sent with every request, e.g. `Authorization: Bearer <token>`
+    #[arg(long = "header", value_name = "NAME: VALUE")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<String>,
+    /// PEM file with the CA certificates to trust instead of the bundled web roots
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<PathBuf>,
+    /// PEM certificate chain presented to the server for mutual TLS
+    #[arg(long, requires = "client_key")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<PathBuf>,
+    /// PEM private key of `--client-cert`
+    #[arg(long, requires = "client_cert")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<PathBuf>,
+}
+
+impl RemoteOptions {
+    /// Resolves the certificate paths relative to `base`.
+    pub fn relative_to(&mut self, base: &Path) {
+        for path in [
+            &mut self.ca_cert,
+            &mut self.client_cert,
+            &mut self.client_key,
+        ] {
+            *path = path.as_ref().map(|path| base.join(path));
+        }
+    }
+
+    /// Replaces header values, which usually carry credentials.
+    pub fn redact(&mut self) {
+        for header in &mut self.headers {
+            if let Some((name, _)) = header.split_once(':') {
+                *header = format!("{}: <redacted>", name);
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RemoteError {
+    Config(String),
+    Transport(String),
+    Http { status: u16, body: String },
+    Rpc { code: i64, message: String },
+    InvalidResponse(String),
+}
+
+impl fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RemoteError::Config(reason) => write!(f, "Invalid remote signer settings: {}", reason),
+            RemoteError::Transport(reason) => write!(f, "Remote signer unreachable: {}", reason),
+            RemoteError::Http { status, body } => {
+                write!(f, "Remote signer returned HTTP {}: {}", status, body.trim())
+            }
+            RemoteError::Rpc { code, message } => {
+                write!(f, "Remote signer returned error {}: {}", code, message)
+            }
+            RemoteError::InvalidResponse(reason) => {
+                write!(f, "Remote signer returned an invalid response: {}", reason)
+            }
+        }
+    }
+}
+
+impl Error for RemoteError {}
+
+fn pem_reader(path: &Path) -> Result<BufReader<File>, RemoteError> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| RemoteError::Config(format!("{:?}: {}", path, e)))
+}
+
+fn tls_config(options: &RemoteOptions) -> Result<Arc<ClientConfig>, RemoteError> {
+    let certs = |path: &Path| {
+        let certs = rustls_pemfile::certs(&mut pem_reader(path)?)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| RemoteError::Config(format!("{:?}: {}", path, e)))?;
+        if certs.is_empty() {
+            return Err(RemoteError::Config(format!(
+                "no certificates in {:?}",
+                path
+            )));
+        }
+        Ok(certs)
+    };
+
+    let mut roots = RootCertStore::empty();
+    match &options.ca_cert {
+        Some(path) => {
+            for cert in certs(path)? {
+                roots
+                    .add(cert)
+                    .map_err(|e| RemoteError::Config(format!("{:?}: {}", path, e)))?;
+            }
+        }
+        None => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+    }
+
+    let builder =
+        ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| RemoteError::Config(e.to_string()))?
+            .with_root_certificates(roots);
+    let config = match (&options.client_cert, &options.client_key) {
+        (Some(cert), Some(key)) => {
+            let private_key = rustls_pemfile::private_key(&mut pem_reader(key)?)
+                .map_err(|e| RemoteError::Config(format!("{:?}: {}", key, e)))?
+                .ok_or_else(|| RemoteError::Config(format!("no private key in {:?}", key)))?;
+            builder
+                .with_client_auth_cert(certs(cert)?, private_key)
+                .map_err(|e| RemoteError::Config(e.to_string()))?
+        }
+        _ => builder.with_no_client_auth(),
+    };
+    Ok(Arc::new(config))
+}
+
+pub struct RemoteSigner {
+    options: RemoteOptions,
+    headers: Vec<(String, String)>,
+    agent: ureq::Agent,
+}
+
+impl RemoteSigner {
+    pub fn new(options: &RemoteOptions, timeout: Option<Duration>) -> Result<Self, RemoteError> {
+        let headers = options
+            .headers
+            .iter()
+            .map(|header| {
+                header
+                    .split_once(':')
+                    .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                    .ok_or_else(|| {
+                        RemoteError::Config(format!("header {:?} is not `Name: value`", header))
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+
+        let mut agent = ureq::AgentBuilder::new().tls_config(tls_config(options)?);
+        if let Some(timeout) = timeout {
+            agent = agent.timeout(timeout);
+        }
+        Ok(RemoteSigner {
+            options: options.clone(),
+            headers,
+            agent: agent.build(),
+        })
+    }
+
+    fn post(&self, url: &str, body: &Value) -> Result<String, RemoteError> {
+        let mut request = self.agent.post(url).set("Content-Type", "application/json");
+        for (name, value) in &self.headers {
+            request = request.set(name, value);
+        }
+
+        match request.send_string(&body.to_string()) {
+            Ok(response) => response
+                .into_string()
+                .map_err(|e| RemoteError::Transport(e.to_string())),
+            Err(ureq::Error::Status(status, response)) => Err(RemoteError::Http {
+                status,
+                body: response.into_string().unwrap_or_default(),
+            }),
+            Err(ureq::Error::Transport(e)) => Err(RemoteError::Transport(e.to_string())),
+        }
+    }
+
+    fn rpc(&self, method: &str, params: Value) -> Result<Value, RemoteError> {
+        let body = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+        let response = self.post(&self.options.url, &body)?;
+        let mut response: Value = serde_json::from_str(&response)
+            .map_err(|e| RemoteError::InvalidResponse(format!("{}: {}", e, response)))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(RemoteError::Rpc {
+                code: error["code"].as_i64().unwrap_or_default(),
+                message: error["message"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+        Ok(response["result"].take())
+    }
+
+    /// Asks the service to sign `request.digest` as a personal message with the key of
+    /// `request.address`, and reports the address the signature recovers to.
+    pub fn sign(&self, request: &SignRequest) -> Result<SignResponse, RemoteError> {
+        let digest = to_hex_string(request.digest.as_slice());
+        let address = request.address.to_checksum(None);
+
+        let signature = match self.options.api {
+            RemoteApi::EthSign => self.rpc("eth_sign", json!([address, digest]))?,
+            RemoteApi::PersonalSign => self.rpc("personal_sign", json!([digest, address]))?,
+            RemoteApi::Web3signer => {
+                let url = format!(
+                    "{}/api/v1/eth1/sign/{}",
+                    self.options.url.trim_end_matches('/'),
+                    address
+                );
+                self.post(&url, &json!({ "data": digest }))?.trim().into()
+            }
+        };
+
+        let signature: Bytes = signature
+            .as_str()
+            .and_then(|signature| signature.parse().ok())
+            .filter(|signature: &Bytes| signature.len() == 65)
+            .ok_or_else(|| {
+                RemoteError::InvalidResponse(format!("expected a 65 byte signature: {}", signature))
+            })?;
+        let address = recover_signer(&request.digest, &signature)
+            .map_err(|e| RemoteError::InvalidResponse(e.to_string()))?;
+        Ok(SignResponse { signature, address })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_signer::MockSigner;
+    use crate::plugin::PROTOCOL_VERSION;
+    use alloy_primitives::{Address, B256};
+    use alloy_signer_local::PrivateKeySigner;
+
+    fn options(url: String, api: RemoteApi, headers: &[&str]) -> RemoteOptions {
+        RemoteOptions {
+            url,
+            api,
+            headers: headers.iter().map(|header| header.to_string()).collect(),
+            ca_cert: None,
+            client_cert: None,
+            client_key: None,
+        }
+    }
+
+    fn request(address: Address) -> SignRequest {
+        SignRequest {
+            version: PROTOCOL_VERSION,
+            digest: B256::repeat_byte(0x42),
+            sig_type: "SECP256K1".to_string(),
+            address,
+        }
+    }
+
+    #[test]
+    fn test_sign_with_every_api() {
+        let key = PrivateKeySigner::random();
+        let server =
+            MockSigner::bind("127.0.0.1:0", key.clone(), Some("secret".to_string())).unwrap();
+
+        for api in [
+            RemoteApi::EthSign,
+            RemoteApi::PersonalSign,
+            RemoteApi::Web3signer,
+        ] {
+            let options = options(server.url(), api, &["Authorization: Bearer secret"]);
+            let signer = RemoteSigner::new(&options, Some(Duration::from_secs(5))).unwrap();
+
+            let response = signer.sign(&request(key.address())).unwrap();
+
+            assert_eq!(response.address, key.address(), "{:?}", api);
+        }
+    }
+
+    #[test]
+    fn test_remote_errors() {
+        let key = PrivateKeySigner::random();
+        let server =
+            MockSigner::bind("127.0.0.1:0", key.clone(), Some("secret".to_string())).unwrap();
+        let sign = |api, header, address| {
+            let options = options(server.url(), api, &[header]);
+            RemoteSigner::new(&options, Some(Duration::from_secs(5)))
+                .unwrap()
+                .sign(&request(address))
+                .unwrap_err()
+        };
+
+        assert!(matches!(
+            sign(
+                RemoteApi::EthSign,
+                "Authorization: Bearer wrong",
+                key.address()
+            ),
+            RemoteError::Http { status: 401, .. }
+        ));
+        assert!(matches!(
+            sign(RemoteApi::EthSign, "Authorization: Bearer secret", Address::ZERO),
+            RemoteError::Rpc { message, .. } if message.contains("unknown account")
+        ));
+        assert!(matches!(
+            sign(
+                RemoteApi::Web3signer,
+                "Authorization: Bearer secret",
+                Address::ZERO
+            ),
+            RemoteError::Http { status: 404, .. }
+        ));
+        assert!(matches!(
+            RemoteSigner::new(&options(server.url(), RemoteApi::EthSign, &["nope"]), None),
+            Err(RemoteError::Config(_))
+        ));
+    }
+}
@@ -0,0 +1,292 @@
+//! Human-readable Markdown and HTML reports of an attestation.
+
+use alloy_primitives::{Address, B256};
+
+use crate::abi::{SignAttestation, SignatureType};
+use crate::attributes::{Attribute, GROUPS};
+use crate::signature::VerifyAttestation;
+use crate::types::Input;
+
+#[derive(Debug, PartialEq)]
+pub enum VerificationStatus {
+    Unsigned,
+    Valid,
+    Invalid { recovered: Address },
+    Error(String),
+}
+
+impl VerificationStatus {
+    pub fn of(input: &Input) -> Self {
+        if input.signature.is_none() {
+            return VerificationStatus::Unsigned;
+        }
+        match input
+            .encode(SignatureType::SECP256K1, input.signer)
+            .verify()
+        {
+            Ok(verification) if verification.valid => VerificationStatus::Valid,
+            Ok(verification) => VerificationStatus::Invalid {
+                recovered: verification.recovered,
+            },
+            Err(e) => VerificationStatus::Error(e.to_string()),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            VerificationStatus::Unsigned => "unsigned".to_string(),
+            VerificationStatus::Valid => "valid".to_string(),
+            VerificationStatus::Invalid { recovered } => {
+                format!("INVALID, signed by {}", recovered.to_checksum(None))
+            }
+            VerificationStatus::Error(e) => format!("could not be verified: {}", e),
+        }
+    }
+}
+
+struct Report<'a> {
+    input: &'a Input,
+    digest: B256,
+    status: VerificationStatus,
+}
+
+impl<'a> Report<'a> {
+    fn new(input: &'a Input) -> Self {
+        Report {
+            input,
+            digest: input
+                .encode(SignatureType::SECP256K1, input.signer)
+                .signature
+                .hash,
+            status: VerificationStatus::of(input),
+        }
+    }
+
+    fn details(&self) -> Vec<(&'static str, String)> {
+        let input = self.input;
+        let erc_deps: Vec<String> = input
+            .module_attributes
+            .external_dependency
+            .erc_deps
+            .iter()
+            .map(|erc| format!("ERC-{}", erc))
+            .collect();
+
+        vec![
+            (
+                "Auditor",
+                format!("{} ({})", input.auditor.name, input.auditor.uri),
+            ),
+            ("Authors", input.auditor.authors.join(", ")),
+            ("Report", input.report_url.clone()),
+            (
+                "Module",
+                input.module_attributes.module_address.to_checksum(None),
+            ),
+            ("ERC dependencies", erc_deps.join(", ")),
+            ("Signer", input.signer.to_checksum(None)),
+            ("Digest", self.digest.to_string()),
+            ("Signature", self.status.describe()),
+        ]
+    }
+
+    fn risks(&self) -> Vec<(&'static Attribute, bool)> {
+        GROUPS
+            .iter()
+            .flat_map(|group| group.values(&self.input.module_attributes))
+            .filter(|(attribute, value)| attribute.is_risky(*value))
+            .collect()
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+pub fn markdown(input: &Input) -> String {
+    let report = Report::new(input);
+    let mut out = format!("# {}\n\n", markdown_cell(&input.title));
+
+    out.push_str("| | |\n|---|---|\n");
+    for (label, value) in report.details() {
+        out.push_str(&format!("| {} | {} |\n", label, markdown_cell(&value)));
+    }
+
+    out.push_str("\n## Risk flags\n\n");
+    let risks = report.risks();
+    if risks.is_empty() {
+        out.push_str("No risk-relevant flags are set.\n");
+    }
+    for (attribute, value) in risks {
+        out.push_str(&format!(
+            "- **`{}` is {}**: {}\n",
+            attribute.key, value, attribute.description
+        ));
+    }
+
+    for group in GROUPS {
+        out.push_str(&format!(
+            "\n## {}\n\n| Attribute | Value | Description |\n|---|---|---|\n",
+            group.title
+        ));
+        for (attribute, value) in group.values(&input.module_attributes) {
+            let marker = if attribute.is_risky(value) {
+                " ⚠"
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "| `{}`{} | {} | {} |\n",
+                attribute.key,
+                marker,
+                yes_no(value),
+                attribute.description
+            ));
+        }
+    }
+
+    out
+}
+
+/// Plain-text summary of the signed fields and risk flags, short enough to review on a terminal
+/// before signing.
+pub fn text(input: &Input) -> String {
+    let report = Report::new(input);
+    let mut out = format!("{}\n", input.title);
+    for (label, value) in report.details() {
+        out.push_str(&format!("  {}: {}\n", label, value));
+    }
+
+    let risks = report.risks();
+    if risks.is_empty() {
+        out.push_str("No risk-relevant flags are set.\n");
+    } else {
+        out.push_str("Risk flags:\n");
+    }
+    for (attribute, value) in risks {
+        out.push_str(&format!(
+            "  {} is {}: {}\n",
+            attribute.key, value, attribute.description
+        ));
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+const HTML_STYLE: &str = "body { font-family: sans-serif; max-width: 60em; margin: 2em auto; }
+table { border-collapse: collapse; margin-bottom: 1.5em; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; }
+code { font-family: monospace; }
+.risk { background: #fde2e1; }
+.valid { color: #1a7f37; }
+.invalid { color: #cf222e; font-weight: bold; }";
+
+pub fn html(input: &Input) -> String {
+    let report = Report::new(input);
+    let title = escape_html(&input.title);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        title, HTML_STYLE, title
+    );
+
+    out.push_str("<table>\n");
+    for (label, value) in report.details() {
+        let class = match (label, &report.status) {
+            ("Signature", VerificationStatus::Valid) => " class=\"valid\"",
+            ("Signature", VerificationStatus::Unsigned) => "",
+            ("Signature", _) => " class=\"invalid\"",
+            _ => "",
+        };
+        out.push_str(&format!(
+            "<tr><th>{}</th><td{}>{}</td></tr>\n",
+            label,
+            class,
+            escape_html(&value)
+        ));
+    }
+    out.push_str("</table>\n");
+
+    out.push_str("<h2>Risk flags</h2>\n");
+    let risks = report.risks();
+    if risks.is_empty() {
+        out.push_str("<p>No risk-relevant flags are set.</p>\n");
+    } else {
+        out.push_str("<ul>\n");
+        for (attribute, value) in risks {
+            out.push_str(&format!(
+                "<li class=\"risk\"><strong><code>{}</code> is {}</strong>: {}</li>\n",
+                attribute.key,
+                value,
+                escape_html(attribute.description)
+            ));
+        }
+        out.push_str("</ul>\n");
+    }
+
+    for group in GROUPS {
+        out.push_str(&format!(
+            "<h2>{}</h2>\n<table>\n<tr><th>Attribute</th><th>Value</th><th>Description</th></tr>\n",
+            group.title
+        ));
+        for (attribute, value) in group.values(&input.module_attributes) {
+            let class = if attribute.is_risky(value) {
+                " class=\"risk\""
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "<tr{}><td><code>{}</code></td><td>{}</td><td>{}</td></tr>\n",
+                class,
+                attribute.key,
+                yes_no(value),
+                escape_html(attribute.description)
+            ));
+        }
+        out.push_str("</table>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::example;
+
+    #[test]
+    fn test_markdown_highlights_risks() {
+        let rendered = markdown(&example());
+
+        assert!(rendered.contains("- **`moduleOwnerCantRug` is false**"));
+        assert!(rendered.contains("- **`delegateCall` is true**"));
+        assert!(!rendered.contains("- **`upgradeable`"));
+        assert!(rendered.contains("| Signature | unsigned |"));
+    }
+
+    #[test]
+    fn test_html_escapes_input() {
+        let mut input = example();
+        input.title = "<script>".to_string();
+
+        let rendered = html(&input);
+
+        assert!(rendered.contains("<h1>&lt;script&gt;</h1>"));
+        assert!(!rendered.contains("<script>"));
+    }
+}
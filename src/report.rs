@@ -0,0 +1,142 @@
+use alloy_primitives::{Bytes, B256};
+use alloy_sol_types::SolValue;
+use serde::Serialize;
+use std::path::PathBuf;
+
+use attestation_rs::abi::{SignAttestation, SignatureType};
+use attestation_rs::render::VerificationStatus;
+use attestation_rs::signature::Normalization;
+use attestation_rs::types::Input;
+
+use crate::files::Output;
+use crate::signing_log::LogVerification;
+
+/// Result of a command for `--output-format json`.
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<B256>,
+    /// ABI-encoded `AuditSummary`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoded: Option<Bytes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signer: Option<String>,
+    /// `SafeMessage` hash that the owners of a Safe signer sign
+    #[serde(rename = "safeHash", skip_serializing_if = "Option::is_none")]
+    pub safe_hash: Option<B256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Bytes>,
+    /// Normalizations applied to the input's signature
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub normalized: Vec<Normalization>,
+    /// Whether the signature is valid, in `verify` mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid: Option<bool>,
+    /// Detached signatures checked in `verify` mode
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub signatures: Vec<DetachedStatus>,
+    /// Signing log checked in `log-verify` mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log: Option<LogVerification>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<PathBuf>,
+    pub warnings: Vec<String>,
+}
+
+/// Outcome of checking one detached signature file.
+#[derive(Debug, Serialize)]
+pub struct DetachedStatus {
+    pub path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signer: Option<String>,
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Brings the signature of `input` into canonical form, noting each normalization on stderr.
+pub fn normalize(input: &mut Input) -> Result<Vec<Normalization>, Box<dyn std::error::Error>> {
+    let applied = input.normalize_signature()?;
+    for normalization in &applied {
+        eprintln!("signature normalized: {}", normalization);
+    }
+    Ok(applied)
+}
+
+impl Report {
+    pub fn new(mode: &str) -> Self {
+        Report {
+            mode: mode.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Describes `input`, warning if it carries a signature that does not verify.
+    pub fn for_input(mode: &str, input: &Input) -> Self {
+        let summary = input.encode(SignatureType::SECP256K1, input.signer);
+        let mut warnings = Vec::new();
+        match VerificationStatus::of(input) {
+            VerificationStatus::Invalid { recovered } => warnings.push(format!(
+                "signature was made by {}, not the signer",
+                recovered.to_checksum(None)
+            )),
+            VerificationStatus::Error(e) => {
+                warnings.push(format!("signature could not be verified: {}", e))
+            }
+            VerificationStatus::Unsigned | VerificationStatus::Valid => {}
+        }
+
+        Report {
+            mode: mode.to_string(),
+            digest: Some(summary.signature.hash),
+            encoded: Some(Bytes::from(summary.abi_encode())),
+            signer: Some(input.signer.to_checksum(None)),
+            signature: input.signature.as_ref().map(|sig| sig.signature.clone()),
+            warnings,
+            ..Default::default()
+        }
+    }
+
+    pub fn normalized(mut self, applied: Vec<Normalization>) -> Self {
+        self.normalized = applied;
+        self
+    }
+
+    pub fn written_to(mut self, outfile: &Output) -> Self {
+        if let Output::File(path) = outfile {
+            self.output = Some(path.clone());
+        }
+        self
+    }
+
+    pub fn emit(&self) -> Result<(), Box<dyn std::error::Error>> {
+        println!("{}", serde_json::to_string_pretty(self)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secret::PrivateKeySource;
+    use crate::signer::{sign_input, Signer, SignerType};
+    use crate::fixtures::{example, PRIVATE_KEY};
+
+    #[test]
+    fn test_report_warns_about_foreign_signature() {
+        let input = example();
+        let signer = Signer::from(SignerType::PrivateKey(PrivateKeySource {
+            private_key: Some(PRIVATE_KEY.to_string()),
+            ..Default::default()
+        }));
+        let mut signed = sign_input(&input, &signer).unwrap();
+        signed.signer = Default::default();
+
+        let report = Report::for_input("hash", &signed);
+
+        assert_eq!(report.signature, signed.signature.map(|sig| sig.signature));
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].starts_with("signature was made by 0x"));
+    }
+}
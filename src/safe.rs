@@ -0,0 +1,261 @@
+//! Attestations signed by a Safe multisig. The Safe's `isValidSignature(bytes32, bytes)` checks
+//! owner signatures over the EIP-712 `SafeMessage` hash of the attestation digest, concatenated in
+//! ascending owner order. This matches the `CompatibilityFallbackHandler` of Safe 1.3.0 and later.
+
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy_sol_types::SolValue;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+use crate::signature::normalize_signature;
+
+/// Owners and threshold of a Safe, as read from its `getOwners()` and `getThreshold()`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SafeOwners {
+    pub owners: Vec<Address>,
+    pub threshold: usize,
+}
+
+/// The `SafeMessage` hash that owners sign for `digest`, for the Safe at `safe` on `chain_id`.
+pub fn safe_message_hash(safe: Address, chain_id: u64, digest: &B256) -> B256 {
+    let domain_separator = keccak256(
+        (
+            keccak256("EIP712Domain(uint256 chainId,address verifyingContract)"),
+            U256::from(chain_id),
+            safe,
+        )
+            .abi_encode(),
+    );
+    // `isValidSignature(bytes32)` passes the digest on as the 32-byte message `abi.encode(digest)`
+    let message = keccak256(
+        (
+            keccak256("SafeMessage(bytes message)"),
+            keccak256(digest.as_slice()),
+        )
+            .abi_encode(),
+    );
+
+    let mut data = Vec::with_capacity(66);
+    data.extend_from_slice(&[0x19, 0x01]);
+    data.extend_from_slice(domain_separator.as_slice());
+    data.extend_from_slice(message.as_slice());
+    keccak256(data)
+}
+
+/// Signature of one owner over a `SafeMessage` hash, in the Safe's encoding: `v` of 27 or 28 for a
+/// signature over the hash itself (`eth_signTypedData`), or 31 or 32 for one over its EIP-191
+/// personal message (`eth_sign`, `cast wallet sign`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnerSignature {
+    pub owner: Address,
+    pub signature: Bytes,
+}
+
+impl OwnerSignature {
+    /// Identifies the owner of `owners` that made `signature` over `safe_hash`, trying both ways an
+    /// owner may have signed.
+    pub fn new(
+        safe_hash: &B256,
+        signature: &[u8],
+        owners: &[Address],
+    ) -> Result<Self, Box<dyn Error>> {
+        let signature = normalize_signature(signature)?.signature;
+        let parsed = alloy_primitives::Signature::try_from(&signature[..])?;
+        let typed = parsed.recover_address_from_prehash(safe_hash)?;
+        let personal = parsed.recover_address_from_msg(safe_hash)?;
+
+        let (owner, offset) = if owners.contains(&typed) {
+            (typed, 0)
+        } else if owners.contains(&personal) {
+            (personal, 4)
+        } else {
+            return Err(format!(
+                "Signature is by no owner of the Safe: it recovers to {} as typed data or {} as a personal message",
+                typed.to_checksum(None),
+                personal.to_checksum(None)
+            )
+            .into());
+        };
+
+        let mut signature = signature.to_vec();
+        signature[64] += offset;
+        Ok(OwnerSignature {
+            owner,
+            signature: Bytes::from(signature),
+        })
+    }
+}
+
+/// Concatenates owner signatures in ascending owner order, as the Safe requires. Later signatures
+/// by the same owner are dropped.
+pub fn assemble(mut signatures: Vec<OwnerSignature>) -> Bytes {
+    signatures.sort_by_key(|signature| signature.owner);
+    signatures.dedup_by_key(|signature| signature.owner);
+    signatures
+        .iter()
+        .flat_map(|signature| signature.signature.iter().copied())
+        .collect::<Vec<u8>>()
+        .into()
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct SafeVerification {
+    pub safe_hash: B256,
+    /// Owners whose signatures verified, in signature order
+    pub signers: Vec<Address>,
+    pub threshold: usize,
+    pub valid: bool,
+}
+
+/// Checks concatenated owner signatures over the `SafeMessage` hash of `digest` the way the Safe
+/// does: ECDSA signatures only, by distinct owners in ascending order, at least `threshold` of them.
+/// Contract and pre-approved hash signatures (`v` of 0 or 1) cannot be checked offline.
+pub fn verify(
+    safe: Address,
+    chain_id: u64,
+    digest: &B256,
+    signatures: &[u8],
+    owners: &SafeOwners,
+) -> Result<SafeVerification, Box<dyn Error>> {
+    if owners.threshold == 0 || owners.threshold > owners.owners.len() {
+        return Err(format!(
+            "Safe threshold {} is not between 1 and the number of owners, {}",
+            owners.threshold,
+            owners.owners.len()
+        )
+        .into());
+    }
+    if !signatures.len().is_multiple_of(65) {
+        return Err(format!(
+            "Safe signatures are 65 bytes each, got {} bytes",
+            signatures.len()
+        )
+        .into());
+    }
+
+    let safe_hash = safe_message_hash(safe, chain_id, digest);
+    let mut signers: Vec<Address> = Vec::new();
+    for signature in signatures.chunks(65) {
+        let mut signature = signature.to_vec();
+        let owner = match signature[64] {
+            27 | 28 => alloy_primitives::Signature::try_from(&signature[..])?
+                .recover_address_from_prehash(&safe_hash)?,
+            31 | 32 => {
+                signature[64] -= 4;
+                alloy_primitives::Signature::try_from(&signature[..])?
+                    .recover_address_from_msg(safe_hash)?
+            }
+            v => {
+                return Err(
+                    format!("Safe signatures with v {} cannot be verified offline", v).into(),
+                )
+            }
+        };
+        if !owners.owners.contains(&owner) {
+            return Err(format!("{} is not an owner of the Safe", owner.to_checksum(None)).into());
+        }
+        if signers.last().is_some_and(|last| *last >= owner) {
+            return Err("Safe signatures must be by distinct owners in ascending order".into());
+        }
+        signers.push(owner);
+    }
+
+    Ok(SafeVerification {
+        safe_hash,
+        valid: signers.len() >= owners.threshold,
+        signers,
+        threshold: owners.threshold,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+
+    #[test]
+    fn test_safe_message_hash() {
+        let safe = Address::repeat_byte(0x5a);
+        let digest = B256::repeat_byte(0x42);
+        let hash = safe_message_hash(safe, 1, &digest);
+
+        assert_ne!(hash, safe_message_hash(safe, 10, &digest));
+        assert_ne!(hash, safe_message_hash(Address::ZERO, 1, &digest));
+
+        // `DOMAIN_SEPARATOR_TYPEHASH` and `SAFE_MSG_TYPEHASH` as hard-coded in the Safe contracts
+        assert_eq!(
+            keccak256("EIP712Domain(uint256 chainId,address verifyingContract)").to_string(),
+            "0x47e79534a245952e8b16893a336b85a3d9ea9fa8c573f3d803afb92a79469218"
+        );
+        assert_eq!(
+            keccak256("SafeMessage(bytes message)").to_string(),
+            "0x60b3cbf8b4a223d68d641b3b6ddf9a298e7f33710cf3d3a9d1146b5a6150fbca"
+        );
+    }
+
+    #[test]
+    fn test_assemble_and_verify_threshold() {
+        let keys: Vec<PrivateKeySigner> = (0..3).map(|_| PrivateKeySigner::random()).collect();
+        let owners = SafeOwners {
+            owners: keys.iter().map(|key| key.address()).collect(),
+            threshold: 2,
+        };
+        let (safe, chain_id, digest) = (Address::repeat_byte(0x5a), 1, B256::repeat_byte(0x42));
+        let safe_hash = safe_message_hash(safe, chain_id, &digest);
+
+        // One owner signs the typed data hash, the other signs it as a personal message
+        let typed = keys[2].sign_hash_sync(&safe_hash).unwrap();
+        let personal = keys[0].sign_message_sync(safe_hash.as_slice()).unwrap();
+        let signatures = vec![
+            OwnerSignature::new(&safe_hash, &typed.as_bytes(), &owners.owners).unwrap(),
+            OwnerSignature::new(&safe_hash, &personal.as_bytes(), &owners.owners).unwrap(),
+        ];
+        assert!(matches!(signatures[0].signature[64], 27 | 28));
+        assert!(signatures[1].signature[64] >= 31);
+
+        let assembled = assemble(signatures.clone());
+        assert_eq!(assembled.len(), 130);
+        let verification = verify(safe, chain_id, &digest, &assembled, &owners).unwrap();
+        assert!(verification.valid);
+        let mut expected = vec![keys[0].address(), keys[2].address()];
+        expected.sort();
+        assert_eq!(verification.signers, expected);
+
+        let single = assemble(signatures[..1].to_vec());
+        assert!(
+            !verify(safe, chain_id, &digest, &single, &owners)
+                .unwrap()
+                .valid
+        );
+
+        let unsorted: Vec<u8> = [&assembled[65..], &assembled[..65]].concat();
+        assert!(verify(safe, chain_id, &digest, &unsorted, &owners).is_err());
+        assert!(verify(safe, 10, &digest, &assembled, &owners).is_err());
+
+        let stranger = PrivateKeySigner::random()
+            .sign_hash_sync(&safe_hash)
+            .unwrap();
+        let error = OwnerSignature::new(&safe_hash, &stranger.as_bytes(), &owners.owners)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("no owner"), "{}", error);
+    }
+
+    #[test]
+    fn test_verify_rejects_unreachable_thresholds() {
+        let owners = |threshold| SafeOwners {
+            owners: vec![Address::repeat_byte(1)],
+            threshold,
+        };
+        let (safe, digest) = (Address::repeat_byte(0x5a), B256::repeat_byte(0x42));
+
+        for threshold in [0, 2] {
+            let error = verify(safe, 1, &digest, &[], &owners(threshold))
+                .unwrap_err()
+                .to_string();
+            assert!(error.contains("threshold"), "{}", error);
+        }
+        assert!(!verify(safe, 1, &digest, &[], &owners(1)).unwrap().valid);
+    }
+}
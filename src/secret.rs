@@ -0,0 +1,151 @@
+//! Private keys for the `private-key` signer. A key given on the command line is visible to other
+//! processes and ends up in shell history, so it can also be read from an environment variable, a
+//! file, an inherited file descriptor, or a prompt that does not echo it. Keys are read once into
+//! buffers that are zeroed when dropped, and are never passed on to child processes.
+
+use alloy_signer_local::PrivateKeySigner;
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use zeroize::Zeroizing;
+
+/// Where the private key is read from. Without any of these, it is prompted for.
+#[derive(Args, Debug, Clone, Default, Serialize, Deserialize)]
+#[group(multiple = false)]
+#[serde(rename_all = "kebab-case")]
+pub struct PrivateKeySource {
+    /// Private key as hex. Other processes can read it from the command line, prefer the options
+    /// below
+    #[arg(short, long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_key: Option<String>,
+    /// Environment variable holding the private key
+    #[arg(long, value_name = "NAME")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_key_env: Option<String>,
+    /// File holding the private key
+    #[arg(long, value_name = "PATH")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_key_file: Option<PathBuf>,
+    /// Inherited file descriptor to read the private key from, e.g. `3` with `3< key.txt`
+    #[arg(long, value_name = "FD")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_key_fd: Option<u32>,
+}
+
+impl PrivateKeySource {
+    /// Resolves the key file relative to `base`.
+    pub fn relative_to(&mut self, base: &Path) {
+        self.private_key_file = self.private_key_file.as_ref().map(|path| base.join(path));
+    }
+
+    /// Replaces a key given inline.
+    pub fn redact(&mut self) {
+        if let Some(private_key) = &mut self.private_key {
+            *private_key = "<redacted>".to_string();
+        }
+    }
+
+    /// Reads the hex private key from its source.
+    pub fn read(&self) -> Result<Zeroizing<String>, Box<dyn Error>> {
+        if let Some(private_key) = &self.private_key {
+            return Ok(Zeroizing::new(private_key.clone()));
+        }
+        if let Some(name) = &self.private_key_env {
+            return env::var(name)
+                .map(Zeroizing::new)
+                .map_err(|_| format!("Set {} to the private key", name).into());
+        }
+        if let Some(path) = &self.private_key_file {
+            return read_file(path);
+        }
+        if let Some(fd) = self.private_key_fd {
+            return read_fd(fd);
+        }
+
+        if !std::io::stdin().is_terminal() {
+            return Err(
+                "No private key given: use --private-key-env, --private-key-file or \
+                        --private-key-fd, or run in a terminal to be prompted for it"
+                    .into(),
+            );
+        }
+        Ok(Zeroizing::new(rpassword::prompt_password("Private key: ")?))
+    }
+
+    /// Reads the private key and parses it into a signer, whose key is zeroed when dropped.
+    pub fn signer(&self) -> Result<PrivateKeySigner, Box<dyn Error>> {
+        let private_key = self.read()?;
+        PrivateKeySigner::from_str(private_key.trim())
+            .map_err(|e| format!("Invalid private key: {}", e).into())
+    }
+}
+
+fn read_file(path: &Path) -> Result<Zeroizing<String>, Box<dyn Error>> {
+    fs::read_to_string(path)
+        .map(Zeroizing::new)
+        .map_err(|e| format!("Cannot read private key from {:?}: {}", path, e).into())
+}
+
+#[cfg(unix)]
+fn read_fd(fd: u32) -> Result<Zeroizing<String>, Box<dyn Error>> {
+    read_file(&Path::new("/dev/fd").join(fd.to_string()))
+}
+
+#[cfg(not(unix))]
+fn read_fd(_fd: u32) -> Result<Zeroizing<String>, Box<dyn Error>> {
+    Err("--private-key-fd is only supported on Unix".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{scratch_dir, PRIVATE_KEY};
+    use std::os::fd::AsRawFd;
+
+    const ADDRESS: &str = "0x05750cFD135858E346FA982d4Fa94E96053CDbc9";
+
+    #[test]
+    fn test_reads_key_from_env_file_and_fd() {
+        let path = scratch_dir("key").join("key.txt");
+        fs::write(&path, format!("{}\n", PRIVATE_KEY)).unwrap();
+        env::set_var("ATTESTATION_TEST_PRIVATE_KEY", PRIVATE_KEY);
+        let file = fs::File::open(&path).unwrap();
+
+        let sources = [
+            PrivateKeySource {
+                private_key_env: Some("ATTESTATION_TEST_PRIVATE_KEY".to_string()),
+                ..Default::default()
+            },
+            PrivateKeySource {
+                private_key_file: Some(path.clone()),
+                ..Default::default()
+            },
+            PrivateKeySource {
+                private_key_fd: Some(file.as_raw_fd() as u32),
+                ..Default::default()
+            },
+        ];
+        for source in sources {
+            assert_eq!(
+                source.signer().unwrap().address().to_checksum(None),
+                ADDRESS,
+                "{:?}",
+                source
+            );
+        }
+        fs::remove_file(&path).unwrap();
+
+        let missing = PrivateKeySource {
+            private_key_env: Some("ATTESTATION_TEST_UNSET".to_string()),
+            ..Default::default()
+        };
+        let error = missing.signer().unwrap_err().to_string();
+        assert_eq!(error, "Set ATTESTATION_TEST_UNSET to the private key");
+    }
+}
@@ -0,0 +1,370 @@
+use alloy_primitives::{Address, Bytes, Signature, B256};
+use serde::Serialize;
+use std::error::Error;
+use std::fmt;
+
+use crate::abi::{AuditSummary, HashAuditSummary, SignatureType};
+use crate::webauthn;
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Verification {
+    pub digest: B256,
+    pub signer: Address,
+    pub recovered: Address,
+    pub valid: bool,
+    /// Normalizations applied to the signature before recovering
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub normalized: Vec<Normalization>,
+}
+
+/// A change made to bring a signature into the canonical 65-byte `r || s || v` form, with `v` of
+/// 27 or 28 and low `s`, that `Schema.validateSignature` accepts.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Normalization {
+    /// A 64-byte EIP-2098 compact signature was expanded
+    ExpandedCompact,
+    /// `v` was given as 0 or 1 and shifted to 27 or 28
+    ShiftedV,
+}
+
+impl fmt::Display for Normalization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Normalization::ExpandedCompact => write!(f, "expanded EIP-2098 compact signature"),
+            Normalization::ShiftedV => write!(f, "shifted v from 0/1 to 27/28"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct NormalizedSignature {
+    pub signature: Bytes,
+    pub applied: Vec<Normalization>,
+}
+
+/// Recovers the address that signed `digest` as an EIP-191 personal message, which is what
+/// `cast wallet sign` produces and `Schema.validateSignature` checks.
+pub fn recover_signer(digest: &B256, signature: &[u8]) -> Result<Address, Box<dyn Error>> {
+    let signature = alloy_primitives::Signature::try_from(signature)?;
+    Ok(signature.recover_address_from_msg(digest)?)
+}
+
+/// Brings a signature into canonical form. Accepts 65-byte signatures with `v` of 0, 1, 27 or 28
+/// and 64-byte EIP-2098 compact signatures. High `s` values are rejected rather than flipped, as
+/// the signature would then differ from the one the signer produced.
+pub fn normalize_signature(signature: &[u8]) -> Result<NormalizedSignature, Box<dyn Error>> {
+    let mut applied = Vec::new();
+    let (r, s, parity) = match signature.len() {
+        65 => {
+            let parity = match signature[64] {
+                v @ (27 | 28) => v == 28,
+                v @ (0 | 1) => {
+                    applied.push(Normalization::ShiftedV);
+                    v == 1
+                }
+                v => return Err(format!("Unsupported signature v value {}", v).into()),
+            };
+            let (r, s) = signature[..64].split_at(32);
+            (B256::from_slice(r), B256::from_slice(s), parity)
+        }
+        64 => {
+            // The top bit of `s` holds the y parity
+            applied.push(Normalization::ExpandedCompact);
+            let (r, s) = signature.split_at(32);
+            let mut s = B256::from_slice(s);
+            let parity = s[0] & 0x80 != 0;
+            s[0] &= 0x7f;
+            (B256::from_slice(r), s, parity)
+        }
+        len => {
+            return Err(format!(
+                "Expected a 65-byte signature or 64-byte EIP-2098 compact signature, got {} bytes",
+                len
+            )
+            .into())
+        }
+    };
+
+    let signature = Signature::from_scalars_and_parity(r, s, parity)
+        .map_err(|e| format!("Invalid signature: {}", e))?;
+    if signature.normalize_s().is_some() {
+        return Err("Signature has a high s value, which makes it malleable".into());
+    }
+    Ok(NormalizedSignature {
+        signature: Bytes::from(signature.as_bytes().to_vec()),
+        applied,
+    })
+}
+
+/// Address of an uncompressed secp256k1 public key, given as the 65-byte SEC1 point or wrapped in
+/// the DER OCTET STRING that PKCS#11 `CKA_EC_POINT` attributes hold.
+pub fn public_key_address(point: &[u8]) -> Result<Address, Box<dyn Error>> {
+    let point = match point {
+        [0x04, 0x41, rest @ ..] if rest.len() == 65 => rest,
+        _ => point,
+    };
+    match point {
+        [0x04, key @ ..] if key.len() == 64 => Ok(Address::from_raw_public_key(key)),
+        _ => Err(format!(
+            "Expected an uncompressed secp256k1 public key, got {} bytes",
+            point.len()
+        )
+        .into()),
+    }
+}
+
+/// Reads a DER INTEGER of at most 32 bytes, returning it with the bytes that follow.
+fn der_integer(der: &[u8]) -> Option<(B256, &[u8])> {
+    let [0x02, len, rest @ ..] = der else {
+        return None;
+    };
+    let (int, rest) = rest.split_at_checked(*len as usize)?;
+    // Positive integers with the top bit set carry a leading zero byte
+    let int = match int {
+        [0, tail @ ..] => tail,
+        _ => int,
+    };
+    (int.len() <= 32).then(|| (B256::left_padding_from(int), rest))
+}
+
+/// Splits an ECDSA signature into `r` and `s`. PKCS#11 specifies raw `r || s`, but some modules
+/// return a DER `SEQUENCE { r INTEGER, s INTEGER }`, as WebAuthn authenticators do.
+pub(crate) fn ecdsa_scalars(signature: &[u8]) -> Option<(B256, B256)> {
+    if signature.len() == 64 {
+        let (r, s) = signature.split_at(32);
+        return Some((B256::from_slice(r), B256::from_slice(s)));
+    }
+    let [0x30, len, body @ ..] = signature else {
+        return None;
+    };
+    if *len as usize != body.len() {
+        return None;
+    }
+    let (r, rest) = der_integer(body)?;
+    let (s, rest) = der_integer(rest)?;
+    rest.is_empty().then_some((r, s))
+}
+
+/// Converts an ECDSA signature of `prehash` without recovery ID, as HSMs produce, into the 65-byte
+/// `r || s || v` with low `s` that `PackedSig` and `Schema.validateSignature` expect. `v` is the
+/// one under which the signature recovers to `signer`.
+pub fn normalize_ecdsa(
+    signature: &[u8],
+    prehash: &B256,
+    signer: Address,
+) -> Result<Bytes, Box<dyn Error>> {
+    let (r, s) =
+        ecdsa_scalars(signature).ok_or("ECDSA signature is neither r || s nor DER encoded")?;
+    for parity in [false, true] {
+        let signature = Signature::from_scalars_and_parity(r, s, parity)?;
+        let signature = signature.normalize_s().unwrap_or(signature);
+        if signature.recover_address_from_prehash(prehash).ok() == Some(signer) {
+            return Ok(Bytes::from(signature.as_bytes().to_vec()));
+        }
+    }
+    Err(format!(
+        "ECDSA signature does not recover to {}",
+        signer.to_checksum(None)
+    )
+    .into())
+}
+
+pub trait VerifyAttestation {
+    fn verify(&self) -> Result<Verification, Box<dyn Error>>;
+}
+
+impl VerifyAttestation for AuditSummary {
+    fn verify(&self) -> Result<Verification, Box<dyn Error>> {
+        if self.signature.sigType == SignatureType::WEBAUTHN {
+            let digest = self.digest();
+            let (recovered, signed) = webauthn::verify(&digest, &self.signature.signatureData)?;
+            return Ok(Verification {
+                digest,
+                signer: self.signature.signer,
+                recovered,
+                valid: signed
+                    && self.signature.hash == digest
+                    && recovered == self.signature.signer,
+                normalized: Vec::new(),
+            });
+        }
+        if self.signature.sigType != SignatureType::SECP256K1 {
+            return Err(format!(
+                "{:?} signatures cannot be verified offline",
+                self.signature.sigType
+            )
+            .into());
+        }
+
+        // Recover over the recomputed digest so that edits after signing invalidate the signature
+        let digest = self.digest();
+        let normalized = normalize_signature(&self.signature.signatureData)?;
+        let recovered = recover_signer(&digest, &normalized.signature)?;
+
+        Ok(Verification {
+            digest,
+            signer: self.signature.signer,
+            recovered,
+            valid: self.signature.hash == digest && recovered == self.signature.signer,
+            normalized: normalized.applied,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::SignAttestation;
+    use crate::types::tests::example;
+    use alloy_primitives::{uint, Bytes, U256};
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+
+    const SECP256K1_ORDER: U256 =
+        uint!(0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141_U256);
+
+    fn signed_summary(signer: &PrivateKeySigner) -> AuditSummary {
+        let mut input = example();
+        input.signer = signer.address();
+
+        let mut summary = input.encode(SignatureType::SECP256K1, input.signer);
+        let signature = signer
+            .sign_message_sync(summary.signature.hash.as_slice())
+            .unwrap();
+        summary.signature.signatureData = Bytes::from(signature.as_bytes().to_vec());
+        summary
+    }
+
+    #[test]
+    fn test_verify_valid_signature() {
+        let signer = PrivateKeySigner::random();
+        let verification = signed_summary(&signer).verify().unwrap();
+
+        assert!(verification.valid);
+        assert_eq!(verification.recovered, signer.address());
+    }
+
+    #[test]
+    fn test_verify_rejects_modified_summary() {
+        let signer = PrivateKeySigner::random();
+        let mut summary = signed_summary(&signer);
+        summary.title = "Tampered".to_string();
+
+        assert!(!summary.verify().unwrap().valid);
+    }
+
+    #[test]
+    fn test_verify_rejects_other_signer() {
+        let signer = PrivateKeySigner::random();
+        let mut summary = signed_summary(&signer);
+        summary.signature.signer = PrivateKeySigner::random().address();
+
+        assert!(!summary.verify().unwrap().valid);
+    }
+
+    #[test]
+    fn test_verify_webauthn_signature() {
+        let input = example();
+        let mut summary = input.encode(SignatureType::WEBAUTHN, input.signer);
+        let (envelope, signer) =
+            crate::webauthn::tests::passkey_sign(&summary.signature.hash, 0x05);
+        summary.signature.signer = signer;
+        summary.signature.signatureData = envelope;
+
+        let verification = summary.verify().unwrap();
+        assert!(verification.valid);
+        assert_eq!(verification.recovered, signer);
+
+        summary.signature.signer = Address::ZERO;
+        assert!(!summary.verify().unwrap().valid);
+        summary.signature.signer = signer;
+        summary.title = "Tampered".to_string();
+        assert!(!summary.verify().unwrap().valid);
+    }
+
+    #[test]
+    fn test_normalize_signature() {
+        let signer = PrivateKeySigner::random();
+        let summary = signed_summary(&signer);
+        let canonical = summary.signature.signatureData.to_vec();
+        let signature = alloy_primitives::Signature::try_from(canonical.as_slice()).unwrap();
+
+        let mut zero_based = canonical.clone();
+        zero_based[64] -= 27;
+        let mut compact = canonical[..64].to_vec();
+        compact[32] |= (canonical[64] == 28) as u8 * 0x80;
+
+        let normalize = |signature: &[u8]| normalize_signature(signature).unwrap();
+        assert_eq!(normalize(&canonical).signature.to_vec(), canonical);
+        assert!(normalize(&canonical).applied.is_empty());
+        assert_eq!(normalize(&zero_based).signature.to_vec(), canonical);
+        assert_eq!(normalize(&zero_based).applied, [Normalization::ShiftedV]);
+        assert_eq!(normalize(&compact).signature.to_vec(), canonical);
+        assert_eq!(
+            normalize(&compact).applied,
+            [Normalization::ExpandedCompact]
+        );
+
+        let mut compact_summary = summary.clone();
+        compact_summary.signature.signatureData = Bytes::from(compact);
+        let verification = compact_summary.verify().unwrap();
+        assert!(verification.valid);
+        assert_eq!(verification.normalized, [Normalization::ExpandedCompact]);
+
+        // The malleable twin: s' = n - s with the opposite parity
+        let mut malleable = canonical.clone();
+        malleable[32..64].copy_from_slice(&(SECP256K1_ORDER - signature.s()).to_be_bytes::<32>());
+        malleable[64] = 27 + 28 - canonical[64];
+        let error = normalize_signature(&malleable).unwrap_err().to_string();
+        assert!(error.contains("malleable"), "{}", error);
+
+        let mut bad_v = canonical.clone();
+        bad_v[64] = 37;
+        assert!(normalize_signature(&bad_v).is_err());
+        assert!(normalize_signature(&canonical[..63]).is_err());
+        assert!(normalize_signature(&[0; 65]).is_err());
+    }
+
+    fn der_integer(int: &[u8]) -> Vec<u8> {
+        let int = &int[int.iter().take_while(|b| **b == 0).count()..];
+        let pad = int[0] & 0x80 != 0;
+        let mut der = vec![0x02, int.len() as u8 + pad as u8];
+        der.extend(pad.then_some(0));
+        der.extend_from_slice(int);
+        der
+    }
+
+    #[test]
+    fn test_normalize_hsm_signature() {
+        use alloy_primitives::eip191_hash_message;
+
+        let signer = PrivateKeySigner::random();
+        let prehash = eip191_hash_message(B256::repeat_byte(0x42));
+        let expected = signer.sign_hash_sync(&prehash).unwrap();
+        // The malleable twin an HSM may return instead: s' = n - s
+        let high_s = (SECP256K1_ORDER - expected.s()).to_be_bytes::<32>();
+        let r = expected.r().to_be_bytes::<32>();
+
+        let raw = [r, high_s].concat();
+        let mut der = der_integer(&r);
+        der.extend(der_integer(&high_s));
+        der.splice(0..0, [0x30, der.len() as u8]);
+
+        for signature in [&raw, &der] {
+            let normalized = normalize_ecdsa(signature, &prehash, signer.address()).unwrap();
+            assert_eq!(normalized.as_ref(), expected.as_bytes());
+        }
+        assert!(normalize_ecdsa(&raw, &prehash, Address::ZERO).is_err());
+        assert!(normalize_ecdsa(&der[1..], &prehash, signer.address()).is_err());
+
+        let point = signer.credential().verifying_key().to_encoded_point(false);
+        let wrapped = [&[0x04, 0x41], point.as_bytes()].concat();
+        assert_eq!(
+            public_key_address(point.as_bytes()).unwrap(),
+            signer.address()
+        );
+        assert_eq!(public_key_address(&wrapped).unwrap(), signer.address());
+        assert!(public_key_address(&point.as_bytes()[1..]).is_err());
+    }
+}
@@ -0,0 +1,302 @@
+use alloy_primitives::{Address, Bytes, B256};
+use alloy_signer::SignerSync;
+use alloy_signer_local::PrivateKeySigner;
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::PathBuf;
+
+use attestation_rs::abi::{SignAttestation, SignatureType};
+use attestation_rs::detached::DetachedSignature;
+use attestation_rs::signature::{normalize_signature, recover_signer};
+use attestation_rs::types::{Input, JsonSignature, JsonSignatureType};
+
+use crate::airgap::SigningRequest;
+#[cfg(feature = "pkcs11")]
+use crate::pkcs11::Pkcs11Options;
+use crate::plugin::{
+    CastPlugin, ExternalPlugin, SignRequest, SignerOptions, SignerPlugin, PROTOCOL_VERSION,
+};
+use crate::remote::{RemoteOptions, RemoteSigner};
+use crate::secret::PrivateKeySource;
+
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize)]
+#[command(author, version, about, long_about = None)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum SignerType {
+    /// Interactive mode
+    Interactive,
+    Ledger,
+    Trezor,
+
+    /// Private key mode
+    PrivateKey(PrivateKeySource),
+
+    /// External signer plugin speaking the JSON protocol on stdin/stdout
+    Plugin {
+        /// Executable to run
+        #[arg(long)]
+        command: PathBuf,
+        /// Arguments passed to the plugin, after `--`
+        #[arg(last = true)]
+        #[serde(default)]
+        args: Vec<String>,
+    },
+
+    /// Remote JSON-RPC or Web3Signer signing service
+    Remote(RemoteOptions),
+
+    /// secp256k1 key in an HSM, through its PKCS#11 module
+    #[cfg(feature = "pkcs11")]
+    Pkcs11(Pkcs11Options),
+}
+
+pub fn to_hex_string(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("0x{}", hex)
+}
+
+pub fn from_hex_string(hex_string: &str) -> Result<Vec<u8>, hex::FromHexError> {
+    // Remove "0x" prefix if present
+    let cleaned_hex = hex_string.strip_prefix("0x").unwrap_or(hex_string);
+
+    // Use the hex crate to decode the string
+    hex::decode(cleaned_hex)
+}
+
+impl SignerType {
+    /// Whether signatures are produced in-process, without prompts or hardware, so that several
+    /// digests can be signed in parallel.
+    pub fn is_native(&self) -> bool {
+        matches!(self, SignerType::PrivateKey(_))
+    }
+
+    /// Name of the backend, as in the `type` of signer profiles.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SignerType::Interactive => "interactive",
+            SignerType::Ledger => "ledger",
+            SignerType::Trezor => "trezor",
+            SignerType::PrivateKey(_) => "private-key",
+            SignerType::Plugin { .. } => "plugin",
+            SignerType::Remote(_) => "remote",
+            #[cfg(feature = "pkcs11")]
+            SignerType::Pkcs11(_) => "pkcs11",
+        }
+    }
+}
+
+/// A signer backend with the settings it is invoked with.
+#[derive(Debug, Clone)]
+pub struct Signer {
+    pub signer_type: SignerType,
+    pub options: SignerOptions,
+    /// Private key read by `unlock`, so that it is prompted for or read only once
+    key: Option<PrivateKeySigner>,
+}
+
+impl From<SignerType> for Signer {
+    fn from(signer_type: SignerType) -> Self {
+        Signer {
+            signer_type,
+            options: SignerOptions::default(),
+            key: None,
+        }
+    }
+}
+
+impl Signer {
+    pub fn is_native(&self) -> bool {
+        self.signer_type.is_native()
+    }
+
+    /// Reads the private key of a `private-key` signer up front. Otherwise it is read again for
+    /// every signature.
+    pub fn unlock(mut self) -> Result<Self, Box<dyn Error>> {
+        if let SignerType::PrivateKey(source) = &self.signer_type {
+            self.key = Some(source.signer()?);
+        }
+        Ok(self)
+    }
+
+    /// Signs `digest` as an EIP-191 personal message with the key of `address`.
+    pub fn sign(&self, digest: &B256, address: Address) -> Result<Bytes, Box<dyn Error>> {
+        let request = SignRequest {
+            version: PROTOCOL_VERSION,
+            digest: *digest,
+            sig_type: format!("{:?}", SignatureType::SECP256K1),
+            address,
+        };
+        let cast = |wallet| CastPlugin {
+            wallet,
+            options: &self.options,
+        };
+
+        let response = match &self.signer_type {
+            SignerType::PrivateKey(source) => {
+                let signature = match &self.key {
+                    Some(key) => key.sign_message_sync(digest.as_slice())?,
+                    None => source.signer()?.sign_message_sync(digest.as_slice())?,
+                };
+                return Ok(Bytes::from(signature.as_bytes().to_vec()));
+            }
+            SignerType::Plugin { command, args } => ExternalPlugin {
+                command: command.clone(),
+                args: args.clone(),
+                timeout: self.options.timeout,
+            }
+            .sign(&request)?,
+            SignerType::Remote(options) => {
+                RemoteSigner::new(options, self.options.timeout)?.sign(&request)?
+            }
+            #[cfg(feature = "pkcs11")]
+            SignerType::Pkcs11(options) => crate::pkcs11::sign(options, &request)?,
+            SignerType::Interactive => cast("--interactive").sign(&request)?,
+            SignerType::Ledger => cast("--ledger").sign(&request)?,
+            SignerType::Trezor => cast("--trezor").sign(&request)?,
+        };
+        if response.address != address {
+            return Err(format!(
+                "Signer used {}, but {} was requested",
+                response.address.to_checksum(None),
+                address.to_checksum(None)
+            )
+            .into());
+        }
+        Ok(response.signature)
+    }
+}
+
+/// Brings `signature` over `digest` into canonical form, checking that it was made by `address`,
+/// e.g. not by the wrong key or hardware wallet account.
+fn check_signature(
+    digest: &B256,
+    signature: &[u8],
+    address: Address,
+    whose: &str,
+) -> Result<Bytes, Box<dyn Error>> {
+    let normalized = normalize_signature(signature)?;
+    for normalization in &normalized.applied {
+        eprintln!("signer output normalized: {}", normalization);
+    }
+    let signature = normalized.signature;
+    let recovered = recover_signer(digest, &signature)?;
+    if recovered != address {
+        return Err(format!(
+            "Signature was made by {}, but {} is {}",
+            recovered.to_checksum(None),
+            whose,
+            address.to_checksum(None)
+        )
+        .into());
+    }
+    Ok(signature)
+}
+
+fn digest(input: &Input) -> B256 {
+    input
+        .encode(SignatureType::SECP256K1, input.signer)
+        .signature
+        .hash
+}
+
+/// Returns a copy of `input` carrying `signature`, which must be a signature over its digest by
+/// `input.signer`, e.g. one made on an offline machine.
+pub fn attach_signature(input: &Input, signature: &[u8]) -> Result<Input, Box<dyn Error>> {
+    let digest = digest(input);
+    let signature = check_signature(&digest, signature, input.signer, "the input's signer")?;
+    let mut signed = input.clone();
+    signed.signature = Some(JsonSignature {
+        hash: to_hex_string(digest.as_slice()),
+        signature,
+        sig_type: JsonSignatureType::Secp256k1,
+    });
+    Ok(signed)
+}
+
+/// Returns a copy of `input` carrying a signature over its digest by `input.signer`.
+pub fn sign_input(input: &Input, signer: &Signer) -> Result<Input, Box<dyn Error>> {
+    let signature = signer.sign(&digest(input), input.signer)?;
+    attach_signature(input, &signature)
+}
+
+/// Signs the digest of `input` as `address`, which may be a co-signer rather than the input's
+/// signer, leaving the input itself untouched.
+pub fn sign_detached(
+    input: &Input,
+    signer: &Signer,
+    address: Address,
+) -> Result<DetachedSignature, Box<dyn Error>> {
+    let digest = digest(input);
+    let signature = signer.sign(&digest, address)?;
+    let whose = if address == input.signer {
+        "the input's signer"
+    } else {
+        "the requested signer"
+    };
+    let signature = check_signature(&digest, &signature, address, whose)?;
+    Ok(DetachedSignature::new(digest, address, signature))
+}
+
+/// Signs the digest of a signing request prepared on another machine, after recomputing it from
+/// the request's input.
+pub fn sign_request(
+    request: &SigningRequest,
+    signer: &Signer,
+) -> Result<DetachedSignature, Box<dyn Error>> {
+    let digest = request.verify()?;
+    let signature = signer.sign(&digest, request.signer)?;
+    let signature = check_signature(&digest, &signature, request.signer, "the requested signer")?;
+    Ok(DetachedSignature::new(digest, request.signer, signature))
+}
+
+/// Signs the `SafeMessage` hash `safe_hash` as `owner`, one of the Safe's owners, for `safe` mode
+/// to assemble with the other owners' signatures.
+pub fn sign_safe_hash(
+    safe_hash: &B256,
+    signer: &Signer,
+    owner: Address,
+) -> Result<DetachedSignature, Box<dyn Error>> {
+    let signature = signer.sign(safe_hash, owner)?;
+    let signature = check_signature(safe_hash, &signature, owner, "the owner")?;
+    Ok(DetachedSignature::new(*safe_hash, owner, signature))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{example, PRIVATE_KEY};
+    use attestation_rs::safe::{self, OwnerSignature};
+
+    #[test]
+    fn test_signature_must_recover_to_signer() {
+        let signer = Signer::from(SignerType::PrivateKey(PrivateKeySource {
+            private_key: Some(PRIVATE_KEY.to_string()),
+            ..Default::default()
+        }));
+        let mut input = example();
+        assert!(sign_input(&input, &signer).is_ok());
+
+        input.signer = Default::default();
+        let error = sign_input(&input, &signer).unwrap_err().to_string();
+        assert!(error.contains("but the input's signer is"), "{}", error);
+    }
+
+    #[test]
+    fn test_owner_signs_safe_hash() {
+        let signer = Signer::from(SignerType::PrivateKey(PrivateKeySource {
+            private_key: Some(PRIVATE_KEY.to_string()),
+            ..Default::default()
+        }));
+        let owner = example().signer;
+        let safe_hash = safe::safe_message_hash(Address::repeat_byte(0x11), 1, &digest(&example()));
+
+        let detached = sign_safe_hash(&safe_hash, &signer, owner).unwrap();
+        assert_eq!(detached.digest, safe_hash);
+        let owner_signature =
+            OwnerSignature::new(&safe_hash, &detached.signature, &[owner]).unwrap();
+        assert_eq!(owner_signature.owner, owner);
+
+        assert!(sign_safe_hash(&safe_hash, &signer, Address::ZERO).is_err());
+    }
+}
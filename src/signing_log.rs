@@ -0,0 +1,372 @@
+//! Local record of every signature the tool produces, so that audit firms can account for what
+//! their keys signed. Each signature appends one JSON line holding the hash of the previous entry,
+//! and each entry's hash covers its exact bytes, so editing, removing or reordering entries breaks
+//! the chain. A `.head` file next to the log keeps the number of entries and the hash of the last
+//! one, which catches truncation of the log's tail. Appends hold an OS lock on a `.lock` file next
+//! to the log, so that concurrent processes extend the same chain instead of forking it.
+
+use alloy_primitives::{Address, B256};
+use serde::{Deserialize, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use attestation_rs::types::{checksummed, Input};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    /// Position in the log, from 0
+    pub index: u64,
+    /// Unix time in seconds
+    pub timestamp: u64,
+    pub digest: B256,
+    #[serde(serialize_with = "checksummed")]
+    pub signer: Address,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "checksummed_option"
+    )]
+    pub module_address: Option<Address>,
+    /// SHA-256 of the input file as read, or of the signing request in `sign-request` mode
+    pub input_sha256: B256,
+    /// Signer backend, e.g. `ledger` or `private-key`
+    pub backend: String,
+    /// Hash of the previous entry, zero for the first
+    pub prev: B256,
+}
+
+fn checksummed_option<S: Serializer>(
+    address: &Option<Address>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match address {
+        Some(address) => checksummed(address, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Number of entries in the log and hash of the last one, zero while it is empty.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct LogHead {
+    pub entries: u64,
+    pub hash: B256,
+}
+
+/// Outcome of `log-verify`: how far the chain is intact, and what is wrong after that.
+#[derive(Debug, Serialize)]
+pub struct LogVerification {
+    pub path: PathBuf,
+    /// Entries whose hashes chain up correctly
+    pub entries: u64,
+    /// Hash of the last of those entries
+    pub head: B256,
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// An entry's line, ending in its hash: the body is the entry's JSON object with the `hash` member
+/// spliced in before the closing brace.
+fn line(body: &str, hash: &B256) -> String {
+    format!(
+        "{},\"hash\":\"{}\"}}\n",
+        body.strip_suffix('}').unwrap_or(body),
+        hash
+    )
+}
+
+/// Splits a line written by `line` into the entry's JSON object and its recorded hash.
+fn split_line(line: &str) -> Option<(String, B256)> {
+    let (body, hash) = line.rsplit_once(",\"hash\":\"")?;
+    let hash = hash.strip_suffix("\"}")?.parse().ok()?;
+    Some((format!("{}}}", body), hash))
+}
+
+fn sha256(data: &[u8]) -> B256 {
+    B256::from_slice(&Sha256::digest(data))
+}
+
+pub struct SigningLog {
+    path: PathBuf,
+    /// Serializes appends from the worker threads of `batch`, which the file lock may not do on
+    /// every platform
+    lock: Mutex<()>,
+}
+
+impl SigningLog {
+    pub fn new(path: PathBuf) -> Self {
+        SigningLog {
+            path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// The log's path with `suffix` appended, e.g. `.head`.
+    fn sibling(&self, suffix: &str) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(suffix);
+        PathBuf::from(path)
+    }
+
+    fn head_path(&self) -> PathBuf {
+        self.sibling(".head")
+    }
+
+    fn read_head(&self) -> Result<LogHead, Box<dyn Error>> {
+        match fs::read_to_string(self.head_path()) {
+            Ok(head) => Ok(serde_json::from_str(&head)
+                .map_err(|e| format!("Invalid signing log head {:?}: {}", self.head_path(), e))?),
+            Err(_) if fs::metadata(&self.path).map_or(true, |log| log.len() == 0) => {
+                Ok(LogHead::default())
+            }
+            Err(e) => Err(format!(
+                "Cannot read the head of the signing log {:?}, check it with log-verify: {}",
+                self.path, e
+            )
+            .into()),
+        }
+    }
+
+    /// Records a signature by `signer` over `digest`, made from the file content `file`. The
+    /// entry is written before the signed output, so that no signature is published unlogged. If
+    /// writing the output then fails, the entry stays for a signature that was never published.
+    pub fn append(
+        &self,
+        digest: B256,
+        signer: Address,
+        input: Option<&Input>,
+        file: &[u8],
+        backend: &str,
+    ) -> Result<LogEntry, Box<dyn Error>> {
+        let _guard = self.lock.lock().expect("signing log lock poisoned");
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        // Held until the head is written, released when dropped
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.sibling(".lock"))
+            .map_err(|e| {
+                format!(
+                    "Cannot open the lock of the signing log {:?}: {}",
+                    self.path, e
+                )
+            })?;
+        lock.lock()
+            .map_err(|e| format!("Cannot lock the signing log {:?}: {}", self.path, e))?;
+
+        let head = self.read_head()?;
+        let entry = LogEntry {
+            index: head.entries,
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            digest,
+            signer,
+            title: input.map(|input| input.title.clone()),
+            module_address: input.map(|input| input.module_attributes.module_address),
+            input_sha256: sha256(file),
+            backend: backend.to_string(),
+            prev: head.hash,
+        };
+        let body = serde_json::to_string(&entry)?;
+        let hash = sha256(body.as_bytes());
+
+        let mut log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("Cannot open the signing log {:?}: {}", self.path, e))?;
+        log.write_all(line(&body, &hash).as_bytes())?;
+        log.sync_all()?;
+
+        // Replaced atomically, so that the head never describes a partly written log
+        let head = LogHead {
+            entries: head.entries + 1,
+            hash,
+        };
+        let mut pending = self.head_path().into_os_string();
+        pending.push(".tmp");
+        fs::write(&pending, serde_json::to_string(&head)? + "\n")?;
+        fs::rename(&pending, self.head_path())?;
+        Ok(entry)
+    }
+
+    /// Walks the hash chain and compares its end with the head file. Fails only if the log
+    /// cannot be read; a broken chain is reported as invalid with the first problem found.
+    pub fn verify(&self) -> Result<LogVerification, Box<dyn Error>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(format!("Cannot read the signing log {:?}: {}", self.path, e).into())
+            }
+        };
+        let mut verification = LogVerification {
+            path: self.path.clone(),
+            entries: 0,
+            head: B256::ZERO,
+            valid: false,
+            error: None,
+        };
+
+        for (number, text) in content.lines().enumerate() {
+            let number = number + 1;
+            let Some((body, hash)) = split_line(text) else {
+                verification.error = Some(format!("line {} is not a signing log entry", number));
+                return Ok(verification);
+            };
+            if sha256(body.as_bytes()) != hash {
+                verification.error = Some(format!("entry on line {} was edited", number));
+                return Ok(verification);
+            }
+            let entry: LogEntry = match serde_json::from_str(&body) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    verification.error =
+                        Some(format!("entry on line {} is invalid: {}", number, e));
+                    return Ok(verification);
+                }
+            };
+            if entry.index != verification.entries || entry.prev != verification.head {
+                verification.error = Some(format!(
+                    "entry on line {} does not follow the one before it, entries were removed or reordered",
+                    number
+                ));
+                return Ok(verification);
+            }
+            verification.entries += 1;
+            verification.head = hash;
+        }
+
+        let head = match fs::read_to_string(self.head_path()) {
+            Ok(head) => serde_json::from_str::<LogHead>(&head).ok(),
+            Err(_) if verification.entries == 0 => Some(LogHead::default()),
+            Err(_) => None,
+        };
+        verification.error = match head {
+            None => Some(format!(
+                "the head file {:?} is missing or invalid",
+                self.head_path()
+            )),
+            Some(head) if head.entries > verification.entries => Some(format!(
+                "the log was truncated: the head records {} entries, the log holds {}",
+                head.entries, verification.entries
+            )),
+            Some(head)
+                if head
+                    != (LogHead {
+                        entries: verification.entries,
+                        hash: verification.head,
+                    }) =>
+            {
+                Some("the last entry does not match the head file".to_string())
+            }
+            Some(_) => None,
+        };
+        verification.valid = verification.error.is_none();
+        Ok(verification)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{example, scratch_dir};
+
+    fn temp_log(name: &str) -> SigningLog {
+        SigningLog::new(scratch_dir(&format!("log-{}", name)).join("signing-log.jsonl"))
+    }
+
+    fn append(log: &SigningLog, byte: u8) -> LogEntry {
+        log.append(
+            B256::repeat_byte(byte),
+            example().signer,
+            Some(&example()),
+            b"{}",
+            "private-key",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_entries_chain_up() {
+        let log = temp_log("chain");
+        assert!(log.verify().unwrap().valid);
+
+        let first = append(&log, 1);
+        let second = append(&log, 2);
+        assert_eq!((first.index, second.index), (0, 1));
+        assert_eq!(first.prev, B256::ZERO);
+        assert_eq!(first.title.as_deref(), Some(example().title.as_str()));
+
+        let verification = log.verify().unwrap();
+        assert!(verification.valid, "{:?}", verification.error);
+        assert_eq!(verification.entries, 2);
+        let content = fs::read_to_string(&log.path).unwrap();
+        let (_, hash) = split_line(content.lines().next().unwrap()).unwrap();
+        assert_eq!(second.prev, hash);
+        assert!(content.contains("\"signer\":\"0x05750cFD135858E346FA982d4Fa94E96053CDbc9\""));
+    }
+
+    #[test]
+    fn test_concurrent_appends_extend_one_chain() {
+        let path = temp_log("concurrent").path;
+        std::thread::scope(|scope| {
+            for byte in 0..4 {
+                // Separate instances, like separate processes, share only the file lock
+                let log = SigningLog::new(path.clone());
+                scope.spawn(move || {
+                    for _ in 0..5 {
+                        append(&log, byte);
+                    }
+                });
+            }
+        });
+
+        let verification = SigningLog::new(path).verify().unwrap();
+        assert!(verification.valid, "{:?}", verification.error);
+        assert_eq!(verification.entries, 20);
+    }
+
+    #[test]
+    fn test_detects_edits_removals_and_truncation() {
+        let log = temp_log("tamper");
+        for byte in 1..=3 {
+            append(&log, byte);
+        }
+        let content = fs::read_to_string(&log.path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        let error = |content: String| {
+            fs::write(&log.path, content).unwrap();
+            let verification = log.verify().unwrap();
+            assert!(!verification.valid);
+            verification.error.unwrap()
+        };
+
+        let edited = content.replacen("private-key", "ledger", 1);
+        assert!(error(edited).contains("line 1 was edited"));
+
+        let removed = format!("{}\n{}\n", lines[0], lines[2]);
+        assert!(error(removed).contains("line 2 does not follow"));
+
+        let truncated = format!("{}\n{}\n", lines[0], lines[1]);
+        assert!(error(truncated).contains("truncated"));
+
+        // Appending refuses to continue a log whose head is gone
+        fs::write(&log.path, &content).unwrap();
+        fs::remove_file(log.head_path()).unwrap();
+        assert!(error(content).contains("head file"));
+        assert!(log
+            .append(B256::ZERO, Address::ZERO, None, b"", "ledger")
+            .is_err());
+    }
+}
@@ -1,30 +1,114 @@
 use alloy_primitives::{Address, Bytes};
-use serde::{Deserialize, Serialize};
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::error::Error;
+use std::fmt;
+
+use crate::signature::{normalize_signature, Normalization};
+
+/// Serializes addresses with their EIP-55 checksum, like they are usually written in inputs.
+pub fn checksummed<S: Serializer>(address: &Address, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&address.to_checksum(None))
+}
+
+/// Deserializes addresses from strings only. YAML reads an unquoted `0x…` that fits in an integer
+/// as one, so numbers are refused with a hint to quote them rather than a generic type error.
+pub fn address<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Address, D::Error> {
+    struct AddressVisitor;
+
+    impl AddressVisitor {
+        fn number<E: de::Error>(number: impl fmt::Display) -> E {
+            E::custom(format!(
+                "address read as the number {}, quote it, e.g. '0x…'",
+                number
+            ))
+        }
+    }
+
+    impl Visitor<'_> for AddressVisitor {
+        type Value = Address;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a 0x-prefixed address string")
+        }
+
+        fn visit_str<E: de::Error>(self, address: &str) -> Result<Address, E> {
+            address.parse().map_err(E::custom)
+        }
+
+        fn visit_u64<E: de::Error>(self, number: u64) -> Result<Address, E> {
+            Err(Self::number(number))
+        }
+
+        fn visit_i64<E: de::Error>(self, number: i64) -> Result<Address, E> {
+            Err(Self::number(number))
+        }
+
+        fn visit_f64<E: de::Error>(self, number: f64) -> Result<Address, E> {
+            Err(Self::number(number))
+        }
+    }
+
+    deserializer.deserialize_any(AddressVisitor)
+}
 
 // JSON input structs
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Input {
+/// An attestation input. `Auditor` is `JsonAuditor` once any auditor profile is resolved, see
+/// `profiles::ProfiledInput` for inputs as written.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Input<Auditor = JsonAuditor> {
     pub title: String,
-    pub auditor: JsonAuditor,
+    pub auditor: Auditor,
     #[serde(rename = "reportUrl")]
     pub report_url: String,
-    #[serde(rename = "signer")]
+    #[serde(
+        rename = "signer",
+        serialize_with = "checksummed",
+        deserialize_with = "address"
+    )]
     pub signer: Address,
     #[serde(rename = "moduleAttributes")]
     pub module_attributes: JsonModuleAttributes,
     pub signature: Option<JsonSignature>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Input {
+    /// Canonical form of input files: keys in struct order, pretty-printed with EIP-55 addresses and
+    /// a trailing newline, so that re-signing a file only changes the lines that differ.
+    pub fn to_canonical_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("input serializes to JSON") + "\n"
+    }
+
+    /// Brings the signature, if any, into canonical form and returns the normalizations applied.
+    /// Fails for malleable or malformed signatures.
+    pub fn normalize_signature(&mut self) -> Result<Vec<Normalization>, Box<dyn Error>> {
+        let Some(signature) = self.signature.as_mut() else {
+            return Ok(Vec::new());
+        };
+        if signature.sig_type != JsonSignatureType::Secp256k1 {
+            // Contract and passkey signatures have formats of their own
+            return Ok(Vec::new());
+        }
+        let normalized = normalize_signature(&signature.signature)?;
+        signature.signature = normalized.signature;
+        Ok(normalized.applied)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct JsonAuditor {
     pub name: String,
     pub uri: String,
     pub authors: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct JsonModuleAttributes {
-    #[serde(rename = "moduleAddress")]
+    #[serde(
+        rename = "moduleAddress",
+        serialize_with = "checksummed",
+        deserialize_with = "address"
+    )]
     pub module_address: Address,
     #[serde(rename = "globalAttributes")]
     pub global_attributes: JsonGlobalAttributes,
@@ -40,7 +124,7 @@ pub struct JsonModuleAttributes {
     pub external_dependency: JsonExternalDependency,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct JsonGlobalAttributes {
     #[serde(rename = "reentrancyProtection")]
     pub reentrancy_protection: bool,
@@ -64,7 +148,7 @@ pub struct JsonGlobalAttributes {
     pub multichain_compatible: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct JsonValidatorAttributes {
     #[serde(rename = "unscopedValidator")]
     pub unscoped_validator: bool,
@@ -73,7 +157,7 @@ pub struct JsonValidatorAttributes {
     pub multiplexer: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct JsonExecutorAttributes {
     #[serde(rename = "handlesUserAssets")]
     pub handles_user_assets: bool,
@@ -87,14 +171,14 @@ pub struct JsonExecutorAttributes {
     pub deterministic_execution: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct JsonFallbackAttributes {
     #[serde(rename = "compatibilityFeature")]
     pub compatibility_feature: bool,
     pub callbacks: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct JsonHookAttributes {
     #[serde(rename = "defaultAllow")]
     pub default_allow: bool,
@@ -108,7 +192,7 @@ pub struct JsonHookAttributes {
     pub user_control: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct JsonExternalDependency {
     pub oracle: bool,
     pub bridges: bool,
@@ -130,4 +214,59 @@ pub struct JsonExternalDependency {
 pub struct JsonSignature {
     pub hash: String,
     pub signature: Bytes,
+    /// Left out for SECP256K1, so that ECDSA-signed files don't change
+    #[serde(
+        rename = "sigType",
+        default,
+        skip_serializing_if = "JsonSignatureType::is_default"
+    )]
+    pub sig_type: JsonSignatureType,
+}
+
+/// `SignatureType` of a signed input. `ERC1271` signatures are checked by calling
+/// `isValidSignature` on the signer contract, e.g. a Safe. `WEBAUTHN` signatures are ABI-encoded
+/// `WebAuthnSignature` passkey assertions.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum JsonSignatureType {
+    #[default]
+    #[serde(rename = "SECP256K1")]
+    Secp256k1,
+    #[serde(rename = "ERC1271")]
+    Erc1271,
+    #[serde(rename = "WEBAUTHN")]
+    WebAuthn,
+}
+
+impl JsonSignatureType {
+    fn is_default(&self) -> bool {
+        *self == JsonSignatureType::default()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const EXAMPLE_JSON: &str = include_str!("../attestation/example.json");
+
+    /// The example attestation, shared by the tests of the other modules.
+    pub(crate) fn example() -> Input {
+        serde_json::from_str(EXAMPLE_JSON).unwrap()
+    }
+
+    #[test]
+    fn test_canonical_json_is_checksummed_and_stable() {
+        let input = example();
+        let canonical = input.to_canonical_json();
+
+        assert!(canonical.contains("\"signer\": \"0x05750cFD135858E346FA982d4Fa94E96053CDbc9\""));
+        assert!(
+            canonical.contains("\"moduleAddress\": \"0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48\"")
+        );
+        assert!(canonical.ends_with("}\n"));
+
+        let reparsed: Input = serde_json::from_str(&canonical).unwrap();
+        assert_eq!(reparsed, input);
+        assert_eq!(reparsed.to_canonical_json(), canonical);
+    }
 }
@@ -0,0 +1,52 @@
+//! `wasm-bindgen` exports for browser tooling. Inputs and outputs are JSON strings or 0x-prefixed
+//! hex so that callers do not need to mirror the Rust types.
+
+use alloy_primitives::{hex, Bytes};
+use alloy_sol_types::SolValue;
+use wasm_bindgen::prelude::*;
+
+use crate::abi::{decode_summary, HashAuditSummary, SignAttestation, SignatureType};
+use crate::signature::VerifyAttestation;
+use crate::types::Input;
+
+fn to_js_error<E: std::fmt::Display>(error: E) -> JsValue {
+    JsValue::from_str(&error.to_string())
+}
+
+fn parse_input(input_json: &str) -> Result<Input, JsValue> {
+    serde_json::from_str(input_json).map_err(to_js_error)
+}
+
+/// ABI-encodes the `AuditSummary` for an `Input` JSON document.
+#[wasm_bindgen]
+pub fn encode(input_json: &str) -> Result<String, JsValue> {
+    let input = parse_input(input_json)?;
+    let summary = input.encode(SignatureType::SECP256K1, input.signer);
+    Ok(Bytes::from(summary.abi_encode()).to_string())
+}
+
+/// Returns the digest that has to be signed for an `Input` JSON document.
+#[wasm_bindgen]
+pub fn digest(input_json: &str) -> Result<String, JsValue> {
+    let input = parse_input(input_json)?;
+    let summary = input.encode(SignatureType::SECP256K1, input.signer);
+    Ok(summary.digest().to_string())
+}
+
+/// Decodes ABI-encoded `AuditSummary` hex into `Input` JSON.
+#[wasm_bindgen]
+pub fn decode(data_hex: &str) -> Result<String, JsValue> {
+    let data = hex::decode(data_hex).map_err(to_js_error)?;
+    let summary = decode_summary(&data).map_err(to_js_error)?;
+    let input = Input::decode(&summary).map_err(to_js_error)?;
+    serde_json::to_string(&input).map_err(to_js_error)
+}
+
+/// Verifies the signature of a signed `Input` JSON document and returns the verification as JSON.
+#[wasm_bindgen]
+pub fn verify(input_json: &str) -> Result<String, JsValue> {
+    let input = parse_input(input_json)?;
+    let summary = input.encode(SignatureType::SECP256K1, input.signer);
+    let verification = summary.verify().map_err(to_js_error)?;
+    serde_json::to_string(&verification).map_err(to_js_error)
+}
@@ -0,0 +1,296 @@
+//! Passkey signatures: P-256 ECDSA over a WebAuthn assertion whose challenge is the attestation
+//! digest, as `navigator.credentials.get()` returns it. On-chain, `Schema.sol` checks them with the
+//! RIP-7212 P-256 precompile.
+//!
+//! P-256 keys have no Ethereum address, so the `signer` of a `WEBAUTHN` signature is the last 20
+//! bytes of `keccak256(x || y)` over the public key carried in the envelope.
+
+use alloy_primitives::{keccak256, Address, Bytes, B256};
+use alloy_sol_types::SolValue;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use p256::ecdsa::signature::hazmat::PrehashVerifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use p256::EncodedPoint;
+use serde::Deserialize;
+use sha2::{Digest as _, Sha256};
+use std::error::Error;
+
+use crate::abi::WebAuthnSignature;
+use crate::signature::ecdsa_scalars;
+
+const TYPE_GET: &str = "\"type\":\"webauthn.get\"";
+
+/// User presence flag in the authenticator data, after the 32-byte RP ID hash
+const USER_PRESENT: u8 = 0x01;
+
+/// RP ID hash, flags and signature counter, the shortest authenticator data `Schema` accepts
+const MIN_AUTHENTICATOR_DATA: usize = 37;
+
+/// DER prefix of the `SubjectPublicKeyInfo` of a P-256 key, before its uncompressed point
+const SPKI_PREFIX: [u8; 26] = [
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
+];
+
+/// The members of an `AuthenticatorAssertionResponse` in `PublicKeyCredential.toJSON()`, base64url
+/// encoded.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AssertionResponse {
+    authenticator_data: String,
+    #[serde(rename = "clientDataJSON")]
+    client_data_json: String,
+    signature: String,
+}
+
+/// Address standing for the P-256 public key `(x, y)`.
+pub fn p256_address(x: &B256, y: &B256) -> Address {
+    Address::from_word(keccak256([x.as_slice(), y.as_slice()].concat()))
+}
+
+/// The `"challenge":"…"` member that the client data of an assertion over `digest` holds.
+fn challenge(digest: &B256) -> String {
+    format!("\"challenge\":\"{}\"", URL_SAFE_NO_PAD.encode(digest))
+}
+
+/// Whether `data` holds `expected` at `index`.
+fn holds_at(data: &[u8], index: usize, expected: &str) -> bool {
+    data.get(index..)
+        .is_some_and(|rest| rest.starts_with(expected.as_bytes()))
+}
+
+impl WebAuthnSignature {
+    /// Builds the envelope from an assertion response and the credential's public key. The
+    /// signature may be DER encoded, as authenticators return it, or raw `r || s`.
+    pub fn from_assertion(
+        authenticator_data: Bytes,
+        client_data_json: String,
+        signature: &[u8],
+        x: B256,
+        y: B256,
+    ) -> Result<Self, Box<dyn Error>> {
+        let challenge_index = client_data_json
+            .find("\"challenge\":\"")
+            .ok_or("clientDataJSON has no challenge")?;
+        let type_index = client_data_json
+            .find(TYPE_GET)
+            .ok_or("clientDataJSON is not a webauthn.get assertion")?;
+        let (r, s) =
+            ecdsa_scalars(signature).ok_or("P-256 signature is neither r || s nor DER encoded")?;
+
+        Ok(WebAuthnSignature {
+            authenticatorData: authenticator_data,
+            clientDataJSON: client_data_json,
+            challengeIndex: challenge_index.try_into()?,
+            typeIndex: type_index.try_into()?,
+            r,
+            s,
+            x,
+            y,
+        })
+    }
+
+    /// Builds the envelope from the JSON of the `PublicKeyCredential` that
+    /// `navigator.credentials.get()` returns, or of its `response`, and the credential's public
+    /// key. The key is the SPKI that `getPublicKey()` returns at registration, or an uncompressed
+    /// SEC1 point.
+    pub fn from_credential_json(json: &str, public_key: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let credential: serde_json::Value = serde_json::from_str(json)?;
+        let response = credential.get("response").unwrap_or(&credential);
+        let response = AssertionResponse::deserialize(response)
+            .map_err(|e| format!("Not a WebAuthn assertion response: {}", e))?;
+        let decode = |name: &str, value: &str| {
+            URL_SAFE_NO_PAD
+                .decode(value.trim_end_matches('='))
+                .map_err(|e| format!("{} is not base64url: {}", name, e))
+        };
+        let authenticator_data = decode("authenticatorData", &response.authenticator_data)?;
+        let client_data_json =
+            String::from_utf8(decode("clientDataJSON", &response.client_data_json)?)?;
+        let signature = decode("signature", &response.signature)?;
+
+        let point = match public_key.strip_prefix(&SPKI_PREFIX) {
+            Some(point) => point,
+            None => public_key,
+        };
+        let key = VerifyingKey::from_sec1_bytes(point)
+            .map_err(|_| "Public key is neither a P-256 SPKI nor a SEC1 point")?;
+        let point = key.to_encoded_point(false);
+        let (x, y) = (point.x().unwrap(), point.y().unwrap());
+
+        Self::from_assertion(
+            authenticator_data.into(),
+            client_data_json,
+            &signature,
+            B256::from_slice(x),
+            B256::from_slice(y),
+        )
+    }
+
+    /// What the authenticator signs: `sha256(authenticatorData || sha256(clientDataJSON))`.
+    pub fn message_hash(&self) -> B256 {
+        let client_data_hash = Sha256::digest(self.clientDataJSON.as_bytes());
+        let mut hasher = Sha256::new();
+        hasher.update(&self.authenticatorData);
+        hasher.update(client_data_hash);
+        B256::from_slice(&hasher.finalize())
+    }
+}
+
+/// Checks an ABI-encoded `WebAuthnSignature` over `digest`. Returns the address of the key in the
+/// envelope, and whether the assertion is user-present, has `digest` as its challenge and a valid
+/// P-256 signature. Fails for malformed envelopes.
+pub fn verify(digest: &B256, signature_data: &[u8]) -> Result<(Address, bool), Box<dyn Error>> {
+    let auth = WebAuthnSignature::abi_decode(signature_data, true)
+        .map_err(|e| format!("Invalid WebAuthn signature envelope: {}", e))?;
+
+    if auth.authenticatorData.len() < MIN_AUTHENTICATOR_DATA {
+        return Err(format!(
+            "Authenticator data is {} bytes, shorter than {}",
+            auth.authenticatorData.len(),
+            MIN_AUTHENTICATOR_DATA
+        )
+        .into());
+    }
+    let client_data = auth.clientDataJSON.as_bytes();
+    let type_index = usize::try_from(auth.typeIndex).unwrap_or(usize::MAX);
+    if !holds_at(client_data, type_index, TYPE_GET) {
+        return Err("clientDataJSON is not a webauthn.get assertion".into());
+    }
+    let point = EncodedPoint::from_affine_coordinates(&auth.x.0.into(), &auth.y.0.into(), false);
+    let key = VerifyingKey::from_encoded_point(&point)
+        .map_err(|_| "WebAuthn public key is not a P-256 point")?;
+    let signature = Signature::from_scalars(auth.r.0, auth.s.0)
+        .map_err(|_| "Invalid P-256 signature scalars")?;
+
+    let challenge_index = usize::try_from(auth.challengeIndex).unwrap_or(usize::MAX);
+    let valid = auth.authenticatorData[32] & USER_PRESENT != 0
+        && holds_at(client_data, challenge_index, &challenge(digest))
+        && key
+            .verify_prehash(auth.message_hash().as_slice(), &signature)
+            .is_ok();
+
+    Ok((p256_address(&auth.x, &auth.y), valid))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use p256::ecdsa::signature::hazmat::PrehashSigner;
+    use p256::ecdsa::SigningKey;
+
+    /// Signs `digest` the way a passkey would, returning the ABI-encoded envelope and the signer.
+    pub(crate) fn passkey_sign(digest: &B256, flags: u8) -> (Bytes, Address) {
+        let key = SigningKey::from_bytes(&[0x11; 32].into()).unwrap();
+        let point = key.verifying_key().to_encoded_point(false);
+        let (x, y) = (
+            B256::from_slice(point.x().unwrap()),
+            B256::from_slice(point.y().unwrap()),
+        );
+
+        let mut authenticator_data = vec![0xaa; 32];
+        authenticator_data.extend_from_slice(&[flags, 0, 0, 0, 1]);
+        let client_data_json = format!(
+            "{{\"type\":\"webauthn.get\",{},\"origin\":\"https://auditor.example\",\"crossOrigin\":false}}",
+            challenge(digest)
+        );
+        let mut envelope = WebAuthnSignature::from_assertion(
+            authenticator_data.into(),
+            client_data_json,
+            &[0; 64],
+            x,
+            y,
+        )
+        .unwrap();
+        let signature: Signature = key
+            .sign_prehash(envelope.message_hash().as_slice())
+            .unwrap();
+        let der = signature.to_der();
+        let (r, s) = ecdsa_scalars(der.as_bytes()).unwrap();
+        (envelope.r, envelope.s) = (r, s);
+
+        (envelope.abi_encode().into(), p256_address(&x, &y))
+    }
+
+    #[test]
+    fn test_verify_passkey_signature() {
+        let digest = B256::repeat_byte(0x42);
+        let (envelope, signer) = passkey_sign(&digest, USER_PRESENT | 0x04);
+        assert_eq!(verify(&digest, &envelope).unwrap(), (signer, true));
+
+        // Another challenge, no user presence or edited data are invalid, not malformed
+        assert_eq!(
+            verify(&B256::repeat_byte(0x43), &envelope).unwrap(),
+            (signer, false)
+        );
+
+        let (absent, _) = passkey_sign(&digest, 0x04);
+        assert_eq!(verify(&digest, &absent).unwrap(), (signer, false));
+
+        let mut tampered = WebAuthnSignature::abi_decode(&envelope, true).unwrap();
+        let mut authenticator_data = tampered.authenticatorData.to_vec();
+        authenticator_data[36] = 2;
+        tampered.authenticatorData = authenticator_data.into();
+        assert_eq!(
+            verify(&digest, &tampered.abi_encode()).unwrap(),
+            (signer, false)
+        );
+
+        let mut malformed = WebAuthnSignature::abi_decode(&envelope, true).unwrap();
+        malformed.typeIndex = alloy_primitives::U256::ZERO;
+        let error = verify(&digest, &malformed.abi_encode()).unwrap_err();
+        assert!(error.to_string().contains("webauthn.get"), "{}", error);
+    }
+
+    #[test]
+    fn test_import_credential_json() {
+        let digest = B256::repeat_byte(0x42);
+        let (envelope, signer) = passkey_sign(&digest, USER_PRESENT);
+        let envelope = WebAuthnSignature::abi_decode(&envelope, true).unwrap();
+        let key = SigningKey::from_bytes(&[0x11; 32].into()).unwrap();
+        let point = key.verifying_key().to_encoded_point(false);
+        let signature = Signature::from_scalars(envelope.r.0, envelope.s.0).unwrap();
+        let response = format!(
+            r#"{{"authenticatorData":"{}","clientDataJSON":"{}","signature":"{}"}}"#,
+            URL_SAFE_NO_PAD.encode(&envelope.authenticatorData),
+            URL_SAFE_NO_PAD.encode(&envelope.clientDataJSON),
+            URL_SAFE_NO_PAD.encode(signature.to_der().as_bytes()),
+        );
+        let credential = format!(
+            r#"{{"id":"AQID","rawId":"AQID","type":"public-key","response":{}}}"#,
+            response
+        );
+        let spki = [SPKI_PREFIX.as_slice(), point.as_bytes()].concat();
+
+        for (json, public_key) in [
+            (&credential, spki.as_slice()),
+            (&response, point.as_bytes()),
+        ] {
+            let imported = WebAuthnSignature::from_credential_json(json, public_key).unwrap();
+            assert_eq!(imported, envelope);
+            assert_eq!(
+                verify(&digest, &imported.abi_encode()).unwrap(),
+                (signer, true)
+            );
+        }
+
+        assert!(WebAuthnSignature::from_credential_json(&response, &[4; 65]).is_err());
+        assert!(WebAuthnSignature::from_credential_json("{}", point.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_short_authenticator_data_is_rejected() {
+        let digest = B256::repeat_byte(0x42);
+        let (envelope, _) = passkey_sign(&digest, USER_PRESENT);
+        let mut short = WebAuthnSignature::abi_decode(&envelope, true).unwrap();
+        short.authenticatorData = short.authenticatorData[..33].to_vec().into();
+        // Signed again, so that only the length is wrong
+        let key = SigningKey::from_bytes(&[0x11; 32].into()).unwrap();
+        let signature: Signature = key.sign_prehash(short.message_hash().as_slice()).unwrap();
+        (short.r, short.s) = ecdsa_scalars(signature.to_der().as_bytes()).unwrap();
+
+        let error = verify(&digest, &short.abi_encode()).unwrap_err();
+        assert!(error.to_string().contains("shorter than 37"), "{}", error);
+    }
+}
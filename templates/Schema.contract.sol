@@ -0,0 +1,79 @@
+contract Schema {
+    using SignatureCheckerLib for address;
+
+    /// RIP-7212 precompile verifying P-256 signatures
+    address public constant P256_VERIFIER = address(0x100);
+
+    function decode(bytes memory data) public pure returns (AuditSummary memory summary) {
+        summary = abi.decode(data, (AuditSummary));
+    }
+
+    function digest(AuditSummary memory summary) public pure returns (bytes32) {
+        bytes memory data = abi.encode(
+            Digest({title: summary.title, auditor: summary.auditor, moduleAttributes: summary.moduleAttributes})
+        );
+        return keccak256(data);
+    }
+
+    function validateSignature(AuditSummary memory summary) public view returns (bool) {
+        if (summary.signature.sigType == SignatureType.SECP256K1) {
+            address recover =
+                ECDSA.recover(ECDSA.toEthSignedMessageHash(summary.signature.hash), summary.signature.signatureData);
+            return recover == summary.signature.signer;
+        }
+        if (summary.signature.sigType == SignatureType.ERC1271) {
+            return summary.signature.signer.isValidERC1271SignatureNow(
+                summary.signature.hash, summary.signature.signatureData
+            );
+        }
+        if (summary.signature.sigType == SignatureType.WEBAUTHN) {
+            return validateWebAuthn(
+                summary.signature.hash,
+                summary.signature.signer,
+                abi.decode(summary.signature.signatureData, (WebAuthnSignature))
+            );
+        }
+    }
+
+    /// Checks a passkey assertion over `hash`. `signer` must be the address of the P-256 key,
+    /// the last 20 bytes of `keccak256(abi.encode(x, y))`.
+    function validateWebAuthn(bytes32 hash, address signer, WebAuthnSignature memory auth)
+        public
+        view
+        returns (bool)
+    {
+        if (address(uint160(uint256(keccak256(abi.encode(auth.x, auth.y))))) != signer) {
+            return false;
+        }
+        // User presence flag, after the 32-byte RP ID hash
+        if (auth.authenticatorData.length < 37 || auth.authenticatorData[32] & bytes1(0x01) == 0) {
+            return false;
+        }
+        bytes memory clientData = bytes(auth.clientDataJSON);
+        if (!contains(clientData, auth.typeIndex, '"type":"webauthn.get"')) {
+            return false;
+        }
+        bytes memory challenge =
+            abi.encodePacked('"challenge":"', Base64.encode(abi.encodePacked(hash), true, true), '"');
+        if (!contains(clientData, auth.challengeIndex, challenge)) {
+            return false;
+        }
+
+        bytes32 message = sha256(abi.encodePacked(auth.authenticatorData, sha256(clientData)));
+        (bool success, bytes memory result) =
+            P256_VERIFIER.staticcall(abi.encode(message, auth.r, auth.s, auth.x, auth.y));
+        return success && result.length == 32 && abi.decode(result, (uint256)) == 1;
+    }
+
+    function contains(bytes memory data, uint256 index, bytes memory expected) internal pure returns (bool) {
+        if (index > data.length || expected.length > data.length - index) {
+            return false;
+        }
+        for (uint256 i; i < expected.length; i++) {
+            if (data[index + i] != expected[i]) {
+                return false;
+            }
+        }
+        return true;
+    }
+}
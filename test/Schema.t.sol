@@ -2,7 +2,21 @@
 pragma solidity ^0.8.13;
 
 import {Test, console} from "forge-std/Test.sol";
-import {Schema, AuditSummary} from "../src/Schema.sol";
+import {Schema, AuditSummary, SignatureType, WebAuthnSignature} from "../src/Schema.sol";
+import {Base64} from "solady/utils/Base64.sol";
+
+/// Contract signer that accepts exactly one hash, with any signature bytes.
+contract MockERC1271Signer {
+    bytes32 public immutable approvedHash;
+
+    constructor(bytes32 hash) {
+        approvedHash = hash;
+    }
+
+    function isValidSignature(bytes32 hash, bytes calldata) external view returns (bytes4) {
+        return hash == approvedHash ? bytes4(0x1626ba7e) : bytes4(0xffffffff);
+    }
+}
 
 contract SchemaTest is Test {
     Schema public schema;
@@ -16,7 +30,9 @@ contract SchemaTest is Test {
     }
 
     function test_decode() public {
-        string[] memory inputs = new string[](10);
+        // Passed through the environment, so that the key is not on the command line
+        vm.setEnv("ATTESTATION_PRIVATE_KEY", "0x5ed8e65ce779308a499dfcb4b2d37a267847e084d80d1b51b09f0bf97e5b8319");
+        string[] memory inputs = new string[](13);
         inputs[0] = "cargo";
         inputs[1] = "run";
         inputs[2] = "--";
@@ -24,9 +40,13 @@ contract SchemaTest is Test {
         inputs[4] = "./attestation/example.json";
         inputs[5] = "--mode";
         inputs[6] = "cast";
-        inputs[7] = "private-key";
-        inputs[8] = "--private-key";
-        inputs[9] = "0x5ed8e65ce779308a499dfcb4b2d37a267847e084d80d1b51b09f0bf97e5b8319";
+        inputs[7] = "--force";
+        // Kept out of the developer's own signing log
+        inputs[8] = "--signing-log";
+        inputs[9] = "./target/forge-test-signing-log.jsonl";
+        inputs[10] = "private-key";
+        inputs[11] = "--private-key-env";
+        inputs[12] = "ATTESTATION_PRIVATE_KEY";
         vm.ffi(inputs);
 
         string[] memory print = new string[](7);
@@ -62,4 +82,57 @@ contract SchemaTest is Test {
 
         assertTrue(validSig, "validSig");
     }
+
+    function test_validateSignature_erc1271() public {
+        string[] memory print = new string[](7);
+        print[0] = "cargo";
+        print[1] = "run";
+        print[2] = "--";
+        print[3] = "--input";
+        print[4] = "./attestation/example.json";
+        print[5] = "--mode";
+        print[6] = "bytes";
+
+        AuditSummary memory summary = schema.decode(vm.ffi(print));
+        MockERC1271Signer contractSigner = new MockERC1271Signer(summary.signature.hash);
+        summary.signature.sigType = SignatureType.ERC1271;
+        summary.signature.signer = address(contractSigner);
+        summary.signature.signatureData = hex"01";
+
+        assertTrue(schema.validateSignature(summary), "approved hash");
+
+        summary.signature.hash = keccak256("other");
+        assertFalse(schema.validateSignature(summary), "other hash");
+    }
+
+    function test_validateSignature_webauthn() public {
+        bytes32 hash = keccak256("attestation");
+        bytes32 x = bytes32(uint256(1));
+        bytes32 y = bytes32(uint256(2));
+        string memory clientDataJSON = string.concat(
+            '{"type":"webauthn.get","challenge":"',
+            Base64.encode(abi.encodePacked(hash), true, true),
+            '","origin":"https://example.com"}'
+        );
+        WebAuthnSignature memory auth = WebAuthnSignature({
+            authenticatorData: abi.encodePacked(bytes32(0), bytes1(0x01), uint32(0)),
+            clientDataJSON: clientDataJSON,
+            challengeIndex: 23,
+            typeIndex: 1,
+            r: bytes32(uint256(3)),
+            s: bytes32(uint256(4)),
+            x: x,
+            y: y
+        });
+        address signer = address(uint160(uint256(keccak256(abi.encode(x, y)))));
+
+        // The precompile is not in the test EVM, so stand in for it
+        vm.mockCall(schema.P256_VERIFIER(), bytes(""), abi.encode(uint256(1)));
+        assertTrue(schema.validateWebAuthn(hash, signer, auth), "valid assertion");
+        assertFalse(schema.validateWebAuthn(keccak256("other"), signer, auth), "other challenge");
+        assertFalse(schema.validateWebAuthn(hash, address(1), auth), "other key");
+
+        auth.authenticatorData[32] = 0x00;
+        assertFalse(schema.validateWebAuthn(hash, signer, auth), "user not present");
+    }
 }